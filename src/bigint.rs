@@ -0,0 +1,257 @@
+//! A minimal arbitrary-precision unsigned integer. It only supports what is
+//! needed to convert a number between radixes, so that hex runs of any length
+//! can be converted instead of overflowing a fixed-size integer.
+
+use std::fmt;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BigUint {
+    /// Base 2^32 limbs, least significant first, without trailing zero limbs.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Parses `digits` in the given `radix`. Returns `None` if `digits` is
+    /// empty or contains a character that is not a digit in `radix`.
+    pub fn from_str_radix(digits: &str, radix: u32) -> Option<Self> {
+        if digits.is_empty() {
+            return None;
+        }
+        let mut value = Self::default();
+        for c in digits.chars() {
+            value.mul_add_small(radix, c.to_digit(radix)?);
+        }
+        Some(value)
+    }
+
+    /// Formats the value in the given `radix` with lowercase digits.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix out of range: {radix}");
+        if self.limbs.is_empty() {
+            return "0".to_owned();
+        }
+        let mut value = self.clone();
+        let mut digits = vec![];
+        while !value.limbs.is_empty() {
+            let digit = value.div_rem_small(radix);
+            digits.push(char::from_digit(digit, radix).unwrap());
+        }
+        digits.iter().rev().collect()
+    }
+
+    /// The number of significant bits, 0 for 0.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(last) => (self.limbs.len() as u64 - 1) * 32 + u64::from(32 - last.leading_zeros()),
+        }
+    }
+
+    /// The value as the nearest `f64`, or infinity if it is too large.
+    pub fn to_f64(&self) -> f64 {
+        self.to_f64_scaled(0)
+    }
+
+    /// `self * 2^exponent` as the nearest `f64`, ties to even, or infinity if
+    /// it is too large. It is rounded only once, also for subnormals.
+    pub fn to_f64_scaled(&self, exponent: i64) -> f64 {
+        let bits = self.bits();
+        // The exponent of the most significant bit.
+        let top = i128::from(bits) - 1 + i128::from(exponent);
+        if bits == 0 || top < -1075 {
+            return 0.0;
+        }
+        if top > 1023 {
+            return f64::INFINITY;
+        }
+        // 53 significant bits, or fewer for subnormals, down to 2^-1074.
+        let keep = (top + 1075).min(53);
+        let dropped = i128::from(bits) - keep;
+        let (significand, scale) = if dropped <= 0 {
+            let significand = self.to_u64().unwrap() << -dropped;
+            (significand, i128::from(exponent) + dropped)
+        } else {
+            let dropped = dropped as u64;
+            let mut significand = (dropped..bits)
+                .rev()
+                .fold(0, |acc, bit| acc << 1 | u64::from(self.bit(bit)));
+            let half = self.bit(dropped - 1);
+            let sticky = self.any_bit_below(dropped - 1);
+            if half && (sticky || significand & 1 == 1) {
+                significand += 1;
+            }
+            (significand, i128::from(exponent) + i128::from(dropped))
+        };
+        // `scale` is from -1074 to 971, so `2^scale` is exact, and so is the
+        // product unless it overflows after rounding.
+        let pow2 = if scale >= -1022 {
+            f64::from_bits(((scale + 1023) as u64) << 52)
+        } else {
+            f64::from_bits(1 << (scale + 1074))
+        };
+        significand as f64 * pow2
+    }
+
+    /// The value as a `u64`, or `None` if it is too large.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low.into()),
+            [low, high] => Some(u64::from(high) << 32 | u64::from(low)),
+            _ => None,
+        }
+    }
+
+    /// `2^bits`
+    pub fn pow2(bits: u64) -> Self {
+        let mut limbs = vec![0; bits as usize / 32 + 1];
+        limbs[bits as usize / 32] = 1 << (bits % 32);
+        Self { limbs }
+    }
+
+    /// `self - other`, or `None` if `other` is larger than `self`.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if other.limbs.len() > self.limbs.len() {
+            return None;
+        }
+        let mut limbs = self.limbs.clone();
+        let mut borrow = 0;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let subtrahend = u64::from(other.limbs.get(i).copied().unwrap_or(0)) + borrow;
+            let (difference, overflow) = u64::from(*limb).overflowing_sub(subtrahend);
+            *limb = difference as u32;
+            borrow = u64::from(overflow);
+        }
+        if borrow != 0 {
+            return None;
+        }
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Some(Self { limbs })
+    }
+
+    /// Whether the bit with weight `2^bit` is set.
+    fn bit(&self, bit: u64) -> bool {
+        self.limbs
+            .get((bit / 32) as usize)
+            .is_some_and(|limb| limb >> (bit % 32) & 1 == 1)
+    }
+
+    /// Whether any bit with a weight below `2^bit` is set.
+    fn any_bit_below(&self, bit: u64) -> bool {
+        let limb = (bit / 32) as usize;
+        let mask = (1 << (bit % 32)) - 1;
+        self.limbs[..limb.min(self.limbs.len())]
+            .iter()
+            .any(|limb| *limb != 0)
+            || self.limbs.get(limb).is_some_and(|limb| limb & mask != 0)
+    }
+
+    /// `self = self * mul + add`
+    fn mul_add_small(&mut self, mul: u32, add: u32) {
+        let mut carry = u64::from(add);
+        for limb in &mut self.limbs {
+            let product = u64::from(*limb) * u64::from(mul) + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// `self = self / div`, returning the remainder.
+    fn div_rem_small(&mut self, div: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let dividend = (rem << 32) | u64::from(*limb);
+            *limb = (dividend / u64::from(div)) as u32;
+            rem = dividend % u64::from(div);
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        rem as u32
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radix_round_trip() {
+        let tests = [
+            ("0", 16, "0"),
+            ("ff", 16, "255"),
+            ("100000000", 16, "4294967296"),
+            ("1010", 2, "10"),
+            ("755", 8, "493"),
+            (
+                "340282366920938463463374607431768211456",
+                10,
+                "340282366920938463463374607431768211456",
+            ),
+        ];
+        for test in tests {
+            let value = BigUint::from_str_radix(test.0, test.1).unwrap();
+            assert_eq!(value.to_string(), test.2);
+            assert_eq!(value.to_str_radix(test.1), test.0);
+        }
+        assert_eq!(BigUint::from_str_radix("", 16), None);
+        assert_eq!(BigUint::from_str_radix("fg", 16), None);
+    }
+
+    #[test]
+    fn test_checked_sub() {
+        let tests = [
+            ("100", "1", Some("ff")),
+            ("100000000", "ffffffff", Some("1")),
+            ("1", "1", Some("0")),
+            ("1", "2", None),
+            ("0", "100000000", None),
+        ];
+        for test in tests {
+            let a = BigUint::from_str_radix(test.0, 16).unwrap();
+            let b = BigUint::from_str_radix(test.1, 16).unwrap();
+            assert_eq!(
+                a.checked_sub(&b).map(|c| c.to_str_radix(16)).as_deref(),
+                test.2
+            );
+        }
+        assert_eq!(BigUint::pow2(64).to_str_radix(16), "10000000000000000");
+    }
+
+    #[test]
+    fn test_bits() {
+        let tests = [("0", 0), ("1", 1), ("ff", 8), ("100000000", 33)];
+        for test in tests {
+            assert_eq!(BigUint::from_str_radix(test.0, 16).unwrap().bits(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_to_f64_scaled() {
+        let tests = [
+            ("0", 0, 0.0),
+            ("18", -4, 1.5),
+            ("ffffffffffffffff", 0, 18446744073709551615.0),
+            ("10000000000000800000001", -88, 1.0000000000000002),
+            ("18", -1078, 1e-323),
+            ("1", -1076, 0.0),
+            ("3", -1076, 5e-324),
+            ("1", 1024, f64::INFINITY),
+        ];
+        for test in tests {
+            let value = BigUint::from_str_radix(test.0, 16).unwrap();
+            assert_eq!(value.to_f64_scaled(test.1), test.2);
+        }
+    }
+}
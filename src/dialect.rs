@@ -0,0 +1,133 @@
+//! Hex literal dialects of various languages and tools, e.g. `$FF` and `0FFh`.
+
+/// A hex literal dialect. `0xff` is always recognized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// An uppercase prefix, e.g. `0X1F`.
+    UpperX,
+    /// Motorola and 6502 assembler, e.g. `$FF`.
+    Dollar,
+    /// Lisp, e.g. `#xFF`.
+    Lisp,
+    /// Visual Basic, e.g. `&HFF`.
+    Basic,
+    /// Verilog, with or without size and signedness, e.g. `8'hFF` and `'hFF`.
+    Verilog,
+    /// Intel assembler suffix form, e.g. `0FFh`. It must start with a digit.
+    AsmSuffix,
+    /// `_` digit separators in prefixed numbers, e.g. `0xdead_beef`.
+    Underscores,
+    /// `'` digit separators in prefixed numbers, e.g. `0xdead'beef`.
+    Apostrophes,
+    /// Rust and C integer type suffixes after prefixed numbers, e.g. `0xffu8`
+    /// and `0x10UL`. They are kept after the converted number.
+    TypeSuffixes,
+    /// C99 hex floats, e.g. `0x1.8p3` and `0x1p-2f`.
+    HexFloats,
+}
+
+impl Dialect {
+    /// All dialects.
+    pub const ALL: [Dialect; 10] = [
+        Dialect::UpperX,
+        Dialect::Dollar,
+        Dialect::Lisp,
+        Dialect::Basic,
+        Dialect::Verilog,
+        Dialect::AsmSuffix,
+        Dialect::Underscores,
+        Dialect::Apostrophes,
+        Dialect::TypeSuffixes,
+        Dialect::HexFloats,
+    ];
+
+    /// The dialects that are recognized by default. The others are more
+    /// likely to match things that are not hex numbers, such as `24h`.
+    pub const DEFAULT: [Dialect; 5] = [
+        Dialect::UpperX,
+        Dialect::Underscores,
+        Dialect::Apostrophes,
+        Dialect::TypeSuffixes,
+        Dialect::HexFloats,
+    ];
+
+    /// The pattern for the prefix of this dialect, if it is a prefix dialect.
+    fn prefix_pattern(self) -> Option<&'static str> {
+        match self {
+            Dialect::UpperX => Some(r"\b0X"),
+            Dialect::Dollar => Some(r"\B\$"),
+            Dialect::Lisp => Some(r"\B#[xX]"),
+            Dialect::Basic => Some(r"\B&[hH]"),
+            Dialect::Verilog => Some(r"(?:\b[0-9]+|\B)'[sS]?[hH]"),
+            Dialect::AsmSuffix
+            | Dialect::Underscores
+            | Dialect::Apostrophes
+            | Dialect::TypeSuffixes
+            | Dialect::HexFloats => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "upper-x" => Ok(Self::UpperX),
+            "dollar" => Ok(Self::Dollar),
+            "lisp" => Ok(Self::Lisp),
+            "basic" => Ok(Self::Basic),
+            "verilog" => Ok(Self::Verilog),
+            "asm-suffix" => Ok(Self::AsmSuffix),
+            "underscores" => Ok(Self::Underscores),
+            "apostrophes" => Ok(Self::Apostrophes),
+            "type-suffixes" => Ok(Self::TypeSuffixes),
+            "hex-floats" => Ok(Self::HexFloats),
+            _ => Err(format!("invalid dialect: {s}")),
+        }
+    }
+}
+
+/// Returns regex alternatives for hex numbers in `dialects` and the plain
+/// `0x` prefix. Digits are captured in the `hex_prefixed` group, or the
+/// `hex_suffixed` group for [`Dialect::AsmSuffix`]. Type suffixes are
+/// captured in the `type_suffix` group. Hex floats are captured in the
+/// `hex_float`, `exponent` and `float_suffix` groups.
+pub fn patterns(dialects: &[Dialect]) -> Vec<String> {
+    let mut prefixes = vec![r"\b0x"];
+    prefixes.extend(
+        dialects
+            .iter()
+            .filter_map(|dialect| dialect.prefix_pattern()),
+    );
+    let mut separators = String::new();
+    if dialects.contains(&Dialect::Underscores) {
+        separators += "_";
+    }
+    if dialects.contains(&Dialect::Apostrophes) {
+        separators += "'";
+    }
+    let mut prefixed = format!(
+        r"(?:{})(?P<hex_prefixed>[0-9a-fA-F][0-9a-fA-F{separators}]*[0-9a-fA-F])",
+        prefixes.join("|")
+    );
+    if dialects.contains(&Dialect::TypeSuffixes) {
+        prefixed += r"(?P<type_suffix>_?[iu](?:8|16|32|64|128|size)|[uU](?:ll|LL|[lL])?|(?:ll|LL|[lL])[uU]?)?";
+    }
+    let mut patterns = vec![];
+    if dialects.contains(&Dialect::HexFloats) {
+        let prefix = if dialects.contains(&Dialect::UpperX) {
+            "0[xX]"
+        } else {
+            "0x"
+        };
+        patterns.push(format!(
+            r"\b{prefix}(?P<hex_float>[0-9a-fA-F]+\.?[0-9a-fA-F]*|\.[0-9a-fA-F]+)[pP](?P<exponent>[+-]?[0-9]+)(?P<float_suffix>[fFlL])?"
+        ));
+    }
+    patterns.push(prefixed);
+    if dialects.contains(&Dialect::AsmSuffix) {
+        patterns.push(r"\b(?P<hex_suffixed>[0-9][0-9a-fA-F]*)[hH]".to_owned());
+    }
+    patterns
+}
@@ -0,0 +1,155 @@
+//! Fixed-point numbers in Q format, e.g. Q15 or Q16.16.
+
+use crate::bigint::BigUint;
+
+/// A fixed-point format in ARM's Q notation: `Qm.n` is signed with `m`
+/// integer bits, including the sign bit, and `n` fraction bits, e.g. Q16.16.
+/// `Qn` is short for `Q1.n`, e.g. Q15. `UQm.n` and `UQn`, short for `UQ0.n`,
+/// are unsigned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+    pub signed: bool,
+    /// The number of integer bits, including the sign bit if signed.
+    pub integer_bits: u32,
+    pub fraction_bits: u32,
+    /// The number of decimals. By default, just enough to tell apart adjacent
+    /// values, e.g. 5 for 15 fraction bits.
+    pub precision: Option<usize>,
+}
+
+impl FixedPoint {
+    /// The number of bits of the format.
+    pub fn bits(&self) -> u64 {
+        u64::from(self.integer_bits) + u64::from(self.fraction_bits)
+    }
+
+    /// Formats the real number with the bit pattern `value`, negated if
+    /// `negate`. `value` must have at most [`FixedPoint::bits`] bits, which
+    /// must be from 1 to 64.
+    pub(crate) fn format(&self, value: &BigUint, negate: bool) -> String {
+        let bits = value.to_u64().unwrap();
+        let sign_bit = self.bits() - 1;
+        let negative = self.signed && bits >> sign_bit & 1 == 1;
+        let magnitude = if negative {
+            // Two's complement, as wide as the format.
+            (u128::from(bits) ^ ((1 << self.bits()) - 1)) + 1
+        } else {
+            u128::from(bits)
+        };
+
+        let precision = self.precision.unwrap_or_else(|| {
+            (f64::from(self.fraction_bits) * std::f64::consts::LOG10_2).ceil() as usize
+        });
+        let one = 1u128 << self.fraction_bits;
+        let mut integer = magnitude >> self.fraction_bits;
+        let mut fraction = magnitude & (one - 1);
+        let mut decimals = vec![0; precision];
+        for decimal in &mut decimals {
+            fraction *= 10;
+            *decimal = (fraction >> self.fraction_bits) as u8;
+            fraction &= one - 1;
+        }
+        // Round half up.
+        if fraction * 2 >= one {
+            match decimals.iter().rposition(|decimal| *decimal != 9) {
+                Some(last) => {
+                    decimals[last] += 1;
+                    decimals[last + 1..].fill(0);
+                }
+                None => {
+                    decimals.fill(0);
+                    integer += 1;
+                }
+            }
+        }
+
+        let mut formatted = String::new();
+        // Only after rounding is it known whether the value is zero.
+        if (negative != negate) && (integer > 0 || decimals.iter().any(|decimal| *decimal > 0)) {
+            formatted.push('-');
+        }
+        formatted += &integer.to_string();
+        if precision > 0 {
+            formatted.push('.');
+            formatted.extend(decimals.iter().map(|decimal| char::from(b'0' + decimal)));
+        }
+        formatted
+    }
+}
+
+impl std::str::FromStr for FixedPoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid Q format: {s}");
+        let (signed, bits) = match s.strip_prefix("UQ") {
+            Some(bits) => (false, bits),
+            None => (true, s.strip_prefix('Q').ok_or_else(invalid)?),
+        };
+        let (integer_bits, fraction_bits) = match bits.split_once('.') {
+            Some((integer_bits, fraction_bits)) => (integer_bits.parse(), fraction_bits.parse()),
+            None => (Ok(u32::from(signed)), bits.parse()),
+        };
+        let (Ok(integer_bits), Ok(fraction_bits)) = (integer_bits, fraction_bits) else {
+            return Err(invalid());
+        };
+        let bits = integer_bits.saturating_add(fraction_bits);
+        if (signed && integer_bits == 0) || !(1..=64).contains(&bits) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            signed,
+            integer_bits,
+            fraction_bits,
+            precision: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let tests = [
+            ("Q15", None, "4000", "0.50000"),
+            ("Q15", None, "8000", "-1.00000"),
+            ("Q15", None, "ffff", "-0.00003"),
+            ("Q15", Some(2), "7fff", "1.00"),
+            ("Q15", Some(2), "ffff", "0.00"),
+            ("Q16.16", None, "00018000", "1.50000"),
+            ("Q16.16", Some(0), "fffe8000", "-2"),
+            ("Q1.31", None, "c0000000", "-0.5000000000"),
+            ("UQ16", Some(3), "ffff", "1.000"),
+            ("UQ32.32", Some(1), "ffffffffffffffff", "4294967296.0"),
+        ];
+        for test in tests {
+            let fixed_point = FixedPoint {
+                precision: test.1,
+                ..test.0.parse().unwrap()
+            };
+            let value = BigUint::from_str_radix(test.2, 16).unwrap();
+            assert_eq!(fixed_point.format(&value, false), test.3);
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        let tests = [
+            ("Q15", Some((true, 1, 15))),
+            ("Q16.16", Some((true, 16, 16))),
+            ("UQ16", Some((false, 0, 16))),
+            ("UQ8.8", Some((false, 8, 8))),
+            ("Q0.15", None),
+            ("Q32.33", None),
+            ("15", None),
+            ("Qx", None),
+        ];
+        for test in tests {
+            let parsed = test.0.parse::<FixedPoint>().ok();
+            let parsed = parsed.map(|q| (q.signed, q.integer_bits, q.fraction_bits));
+            assert_eq!(parsed, test.1);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Floats: IEEE-754 bit patterns such as `0x3fc00000` and C99 hex float
+//! literals such as `0x1.8p3`.
+
+use crate::bigint::BigUint;
+
+/// A floating-point format to interpret bit patterns as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// IEEE-754 half precision.
+    F16,
+    /// bfloat16, the upper half of an [`FloatFormat::F32`].
+    Bf16,
+    /// IEEE-754 single precision.
+    F32,
+    /// IEEE-754 double precision.
+    F64,
+}
+
+impl FloatFormat {
+    /// The number of bits of the format.
+    pub fn bits(self) -> u64 {
+        match self {
+            FloatFormat::F16 | FloatFormat::Bf16 => 16,
+            FloatFormat::F32 => 32,
+            FloatFormat::F64 => 64,
+        }
+    }
+
+    /// Formats the float with the bit pattern `value`, negated if `negate`.
+    /// `value` must have at most [`FloatFormat::bits`] bits.
+    pub(crate) fn format(self, value: &BigUint, negate: bool) -> String {
+        let bits = value.to_u64().unwrap();
+        match self {
+            FloatFormat::F16 => format_float(f16_to_f32(bits as u16), negate),
+            FloatFormat::Bf16 => format_float(f32::from_bits((bits as u32) << 16), negate),
+            FloatFormat::F32 => format_float(f32::from_bits(bits as u32), negate),
+            FloatFormat::F64 => format_float(f64::from_bits(bits), negate),
+        }
+    }
+}
+
+impl std::str::FromStr for FloatFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "f16" => Ok(Self::F16),
+            "bf16" => Ok(Self::Bf16),
+            "f32" => Ok(Self::F32),
+            "f64" => Ok(Self::F64),
+            _ => Err(format!("invalid float format: {s}")),
+        }
+    }
+}
+
+/// Formats the hex float `mantissa * 2^exponent`, negated if `negate`.
+pub(crate) fn format_hex_float(mantissa: &BigUint, exponent: i64, negate: bool) -> String {
+    format_float(mantissa.to_f64_scaled(exponent), negate)
+}
+
+/// Formats `float` as the shortest decimal that round-trips, with an exponent
+/// only if it is very large or small, e.g. `1.5`, `12.0` or `1e-40`.
+fn format_float<F: std::ops::Neg<Output = F> + std::fmt::Debug>(float: F, negate: bool) -> String {
+    if negate {
+        format!("{:?}", -float)
+    } else {
+        format!("{float:?}")
+    }
+}
+
+/// Converts a half precision bit pattern to the `f32` with the same value.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::from(bits >> 10 & 0x1f);
+    let fraction = f32::from(bits & 0x3ff);
+    let magnitude = match exponent {
+        0 => fraction * 2f32.powi(-24),
+        0x1f if fraction == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (fraction + 1024.0) * 2f32.powi(exponent - 25),
+    };
+    sign * magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let tests = [
+            (FloatFormat::F16, "3e00", "1.5"),
+            (FloatFormat::F16, "c000", "-2.0"),
+            (FloatFormat::F16, "0001", "5.9604645e-8"),
+            (FloatFormat::F16, "7c00", "inf"),
+            (FloatFormat::F16, "7e00", "NaN"),
+            (FloatFormat::Bf16, "3fc0", "1.5"),
+            (FloatFormat::F32, "3fc00000", "1.5"),
+            (FloatFormat::F32, "3dcccccd", "0.1"),
+            (FloatFormat::F32, "00000001", "1e-45"),
+            (FloatFormat::F64, "3ff8000000000000", "1.5"),
+            (FloatFormat::F64, "fff0000000000000", "-inf"),
+        ];
+        for test in tests {
+            let value = BigUint::from_str_radix(test.1, 16).unwrap();
+            assert_eq!(test.0.format(&value, false), test.2);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Digit grouping of converted numbers, e.g. `140,737,488,347,136`.
+
+/// How to group the digits of converted numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grouping {
+    /// Put between groups, e.g. `,`, `_`, `'` or a thin space.
+    pub separator: String,
+    /// The number of digits per group, counted from the right.
+    pub size: usize,
+}
+
+impl Grouping {
+    /// The grouping conventionally used for numbers in `locale`, e.g. `de_DE`
+    /// or `fr_FR.UTF-8`. Falls back to `,` for unknown locales.
+    pub fn from_locale(locale: &str) -> Self {
+        let locale = locale.split(['.', '@']).next().unwrap_or_default();
+        let (language, country) = locale.split_once('_').unwrap_or((locale, ""));
+        let separator = match (language, country) {
+            (_, "CH" | "LI") => "'",
+            ("de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el", _) => ".",
+            ("fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk", _) => "\u{2009}",
+            _ => ",",
+        };
+        Self {
+            separator: separator.to_owned(),
+            size: 3,
+        }
+    }
+
+    /// Groups `digits` from the right.
+    pub(crate) fn apply(&self, digits: &str) -> String {
+        let digits: Vec<char> = digits.chars().collect();
+        if self.size == 0 {
+            return digits.into_iter().collect();
+        }
+        let mut grouped = String::new();
+        for (i, digit) in digits.iter().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(self.size) {
+                grouped += &self.separator;
+            }
+            grouped.push(*digit);
+        }
+        grouped
+    }
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Self {
+            separator: ",".to_owned(),
+            size: 3,
+        }
+    }
+}
@@ -0,0 +1,1441 @@
+//! Transform hex numbers to decimal notation in place, and back.
+
+mod bigint;
+mod dialect;
+mod fixed;
+mod float;
+mod grouping;
+mod size;
+mod source;
+mod structured;
+mod table;
+mod template;
+mod words;
+
+use bigint::BigUint;
+use once_cell::sync::Lazy;
+use regex::bytes::{Captures, Regex};
+use std::borrow::Cow;
+use std::io::{BufRead, Write};
+use std::ops::Range;
+use template::Number;
+
+pub use dialect::Dialect;
+pub use fixed::FixedPoint;
+pub use float::FloatFormat;
+pub use grouping::Grouping;
+pub use size::{Sizes, Units};
+pub use source::{hex2dec_source, Language, SourceOptions};
+pub use structured::Shape;
+pub use table::{hex2dec_table, hex2dec_table_stream};
+pub use template::Template;
+
+static DEC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[0-9]{2,}\b").unwrap());
+
+/// What to do with hex numbers that have more than [`Options::max_digits`]
+/// digits, such as hashes and blobs. Any policy but [`LongTokens::Auto`] takes
+/// precedence over [`Options::skipped_shapes`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LongTokens {
+    /// Convert the hex number, unless it has the shape of a hash and
+    /// [`Shape::Hash`] is skipped.
+    #[default]
+    Auto,
+    /// Leave the hex number as is.
+    Keep,
+    /// Convert the hex number like any other.
+    Convert,
+    /// Replace the hex number with the given marker.
+    Replace(String),
+}
+
+impl std::str::FromStr for LongTokens {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            None if s == "auto" => Ok(Self::Auto),
+            None if s == "keep" => Ok(Self::Keep),
+            None if s == "convert" => Ok(Self::Convert),
+            None if s == "replace" => Ok(Self::Replace("…".to_owned())),
+            Some(("replace", marker)) => Ok(Self::Replace(marker.to_owned())),
+            _ => Err(format!("invalid long token policy: {s}")),
+        }
+    }
+}
+
+/// Which hex numbers to convert.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Detection {
+    /// Only numbers that are evidently hex: with a `0x` prefix, with at least
+    /// one of the digits a-f, or in a whitespace separated run of equally wide
+    /// numbers where at least one is evidently hex, such as a hex dump. Plain
+    /// decimal numbers like the `10` in `took 10 ms` are left alone.
+    #[default]
+    Strict,
+    /// Any run of at least two hex digits.
+    Loose,
+}
+
+impl std::str::FromStr for Detection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Self::Strict),
+            "loose" => Ok(Self::Loose),
+            _ => Err(format!("invalid detection mode: {s}")),
+        }
+    }
+}
+
+/// How to pad converted numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+    /// Right-align in the width of the original number.
+    #[default]
+    Right,
+    /// Left-align in the width of the original number.
+    Left,
+    /// Don't pad.
+    None,
+    /// Right-align in the width of the largest number with as many bits as the
+    /// original number has digits for, e.g. 20 for 16 hex digits (64 bits).
+    Bits,
+    /// Right-align in the width of the original number. If the converted
+    /// number is wider, remove spaces after it, keeping at least one, so that
+    /// following columns stay put.
+    Borrow,
+}
+
+impl std::str::FromStr for Padding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Self::Right),
+            "left" => Ok(Self::Left),
+            "none" => Ok(Self::None),
+            "bits" => Ok(Self::Bits),
+            "borrow" => Ok(Self::Borrow),
+            _ => Err(format!("invalid padding: {s}")),
+        }
+    }
+}
+
+/// The bit width to interpret numbers as two's complement signed numbers with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignedWidth {
+    /// A fixed number of bits, e.g. 64. Numbers that don't fit are unsigned.
+    Bits(u64),
+    /// As many bits as the number has digits for, e.g. 32 for 8 hex digits.
+    Inferred,
+}
+
+impl std::str::FromStr for SignedWidth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Inferred),
+            _ => s
+                .parse()
+                .ok()
+                .filter(|bits| *bits > 0)
+                .map(Self::Bits)
+                .ok_or_else(|| format!("invalid bit width: {s}")),
+        }
+    }
+}
+
+/// Options for [`hex2dec_line`] and [`hex2dec_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub detection: Detection,
+    /// Hex numbers with more digits than this, not counting any `0x` prefix,
+    /// are handled according to [`Options::long_tokens`].
+    pub max_digits: usize,
+    pub long_tokens: LongTokens,
+    /// Leave hex numbers without a `0x` prefix that are English words, such
+    /// as `bad` and `cafe`, alone unless they are part of a hex dump.
+    pub english_words: bool,
+    /// Words to leave alone like English words, even if
+    /// [`Options::english_words`] is off.
+    pub word_allowlist: Vec<String>,
+    /// English words to convert anyway.
+    pub word_denylist: Vec<String>,
+    /// Structured tokens such as dates, versions and hashes to leave alone as
+    /// a whole.
+    pub skipped_shapes: Vec<Shape>,
+    /// Hex literal dialects to recognize in addition to `0xff`.
+    pub dialects: Vec<Dialect>,
+    /// Also convert binary numbers such as `0b1010_0001`.
+    pub binary: bool,
+    /// Also convert octal numbers such as `0o755` and, C style, `0755`. Hex
+    /// numbers made up of only octal digits with a leading `0` are then
+    /// treated as octal, unless the line has hex numbers without a prefix, as
+    /// in a hex dump.
+    pub octal: bool,
+    /// The radix numbers are converted to, from 2 to 36. Digits above 9 are
+    /// lowercase letters.
+    pub radix: u32,
+    /// Treat a minus directly before a number with a prefix as part of the
+    /// number, so that e.g. `-0x18` is converted to `-24` as a whole.
+    pub negative_literals: bool,
+    /// Interpret numbers as two's complement signed numbers, so that e.g.
+    /// `0xfffffffffffffff2` with 64 bits is converted to `-14`.
+    pub signed: Option<SignedWidth>,
+    /// Interpret numbers with exactly as many bits as one of these formats,
+    /// e.g. 8 hex digits for [`FloatFormat::F32`], as IEEE-754 bit patterns,
+    /// so that e.g. `0x3fc00000` is converted to `1.5`.
+    pub floats: Vec<FloatFormat>,
+    /// Interpret numbers with exactly as many bits as this format as
+    /// fixed-point numbers, so that e.g. `0x4000` in Q15 is converted to
+    /// `0.50000`. Formats with more than 64 bits are ignored.
+    pub fixed_point: Option<FixedPoint>,
+    /// Render converted numbers as human-readable sizes, e.g. `360.5 KiB`.
+    pub sizes: Option<Sizes>,
+    /// Group the digits of converted numbers, e.g. `140,737,488,347,136`.
+    pub grouping: Option<Grouping>,
+    /// Without [`Options::grouping`], group the digits of converted numbers
+    /// with the separator of the original number, if it has any, e.g.
+    /// `0xFFFF_FFFF` to `4_294_967_295`.
+    pub regroup: bool,
+    pub padding: Padding,
+    /// Replace numbers with this template instead of the converted number,
+    /// e.g. `{dec}/{hex}`.
+    pub format: Option<Template>,
+    /// Keep numbers as they are and append this annotation instead of
+    /// replacing them, e.g. ` ({value})`.
+    pub annotation: Option<Template>,
+}
+
+impl Options {
+    /// The pattern of the numbers to convert.
+    fn pattern(&self) -> String {
+        let mut alternatives = vec![];
+        if self.binary {
+            alternatives.push(r"\b0b(?P<bin>[01](?:[01_]*[01])?)".to_owned());
+        }
+        if self.octal {
+            alternatives
+                .push(r"\b0o(?P<oct>[0-7](?:[0-7_]*[0-7])?)|\b(?P<c_oct>0[0-7]+)".to_owned());
+        }
+        alternatives.extend(dialect::patterns(&self.dialects));
+        alternatives.push(r"\b(?P<hex>[0-9a-fA-F]{2,})".to_owned());
+        format!(r"(?:{})\b", alternatives.join("|"))
+    }
+
+    /// If `token` is a word to leave alone, see [`Options::english_words`].
+    fn is_word(&self, token: &Token) -> bool {
+        if token.radix != 16 || !token.prefix.is_empty() || !token.suffix.is_empty() {
+            return false;
+        }
+        let digits = token.digits.as_str();
+        let listed = |list: &[String]| list.iter().any(|word| word.eq_ignore_ascii_case(digits));
+        if listed(&self.word_denylist) {
+            return false;
+        }
+        listed(&self.word_allowlist) || (self.english_words && words::is_english_word(digits))
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            detection: Detection::default(),
+            max_digits: 32,
+            long_tokens: LongTokens::default(),
+            english_words: true,
+            word_allowlist: vec![],
+            word_denylist: vec![],
+            skipped_shapes: Shape::ALL.to_vec(),
+            dialects: Dialect::DEFAULT.to_vec(),
+            binary: false,
+            octal: false,
+            radix: 10,
+            negative_literals: false,
+            signed: None,
+            floats: vec![],
+            fixed_point: None,
+            sizes: None,
+            grouping: None,
+            regroup: false,
+            padding: Padding::default(),
+            format: None,
+            annotation: None,
+        }
+    }
+}
+
+/// The case of the digits a-f in hex output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Case {
+    #[default]
+    Lower,
+    Upper,
+}
+
+/// Options for [`dec2hex_line`] and [`dec2hex_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dec2HexOptions {
+    /// Put in front of every hex number, e.g. `0x`.
+    pub prefix: String,
+    pub case: Case,
+}
+
+impl Default for Dec2HexOptions {
+    fn default() -> Self {
+        Self {
+            prefix: "0x".to_owned(),
+            case: Case::default(),
+        }
+    }
+}
+
+/// Transforms `input` line by line into `output` with `transform`, e.g.
+/// [`hex2dec_bytes`]. Line endings, including CRLF and a missing final
+/// newline, are preserved exactly.
+pub fn transform_stream(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    transform: impl Fn(&[u8]) -> Vec<u8>,
+) -> std::io::Result<()> {
+    let mut line = vec![];
+    while input.read_until(b'\n', &mut line)? > 0 {
+        output.write_all(&transform(&line))?;
+        line.clear();
+    }
+    output.flush()
+}
+
+/// Converts hex numbers, and binary and octal numbers if enabled in `options`,
+/// in `line` to decimal notation, or the radix in [`Options::radix`], in place.
+/// To convert many lines, use a [`Converter`] instead.
+pub fn hex2dec_line(line: &str, options: &Options) -> String {
+    Converter::new(options.clone()).hex2dec_line(line)
+}
+
+/// Like [`hex2dec_line`] but for lines that might not be valid UTF-8. Bytes
+/// that are not part of a hex number are passed through unchanged.
+pub fn hex2dec_bytes<'a>(line: &'a [u8], options: &Options) -> Cow<'a, [u8]> {
+    Converter::new(options.clone()).hex2dec_bytes(line)
+}
+
+/// [`Options`] prepared for converting many lines, with the regexes they
+/// imply compiled once.
+#[derive(Clone, Debug)]
+pub struct Converter {
+    options: Options,
+    regex: Regex,
+    skipped_shapes_regex: Option<Regex>,
+}
+
+impl Converter {
+    /// Compiles the regexes for `options`.
+    pub fn new(options: Options) -> Self {
+        Self {
+            regex: Regex::new(&options.pattern()).unwrap(),
+            skipped_shapes_regex: structured::pattern(&options.skipped_shapes)
+                .map(|pattern| Regex::new(&pattern).unwrap()),
+            options,
+        }
+    }
+
+    /// Like [`hex2dec_line`] with the options of this converter.
+    pub fn hex2dec_line(&self, line: &str) -> String {
+        String::from_utf8(self.hex2dec_bytes(line.as_bytes()).into_owned()).unwrap()
+    }
+
+    /// Like [`hex2dec_bytes`] with the options of this converter.
+    pub fn hex2dec_bytes<'a>(&self, line: &'a [u8]) -> Cow<'a, [u8]> {
+        hex2dec_bytes_with(line, self, None, |_, _| {})
+    }
+
+    pub(crate) fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// A converter with `options` that reuses the regexes of this one, so
+    /// `options` must only differ in how numbers are converted, not in which
+    /// numbers are found.
+    pub(crate) fn with_options(&self, options: Options) -> Self {
+        debug_assert_eq!(options.pattern(), self.options.pattern());
+        debug_assert_eq!(options.skipped_shapes, self.options.skipped_shapes);
+        Self {
+            options,
+            regex: self.regex.clone(),
+            skipped_shapes_regex: self.skipped_shapes_regex.clone(),
+        }
+    }
+}
+
+/// Like [`hex2dec_bytes`], but if `literals` is given, only numbers that span
+/// exactly one of these ranges are converted. `on_replaced` is called with
+/// the original number and the output after each number is replaced.
+pub(crate) fn hex2dec_bytes_with<'a>(
+    line: &'a [u8],
+    converter: &Converter,
+    literals: Option<&[Range<usize>]>,
+    mut on_replaced: impl FnMut(&str, &mut Vec<u8>),
+) -> Cow<'a, [u8]> {
+    let options = &converter.options;
+    let tokens = find_tokens(line, converter, literals);
+    if tokens.is_empty() {
+        return Cow::Borrowed(line);
+    }
+
+    let default_sizes = Sizes::default();
+    let sizes = options.sizes.as_ref().unwrap_or(&default_sizes);
+    let mut output = Vec::with_capacity(line.len());
+    let mut last_end = 0;
+    // How much wider than the original the last converted number is, see
+    // [`Padding::Borrow`].
+    let mut overflow = 0;
+    for (token, convert) in tokens {
+        output.extend_from_slice(borrow_spaces(
+            &line[last_end..token.range.start],
+            std::mem::take(&mut overflow),
+        ));
+        last_end = token.range.end;
+        let original = &line[token.range.clone()];
+        if !convert {
+            output.extend_from_slice(original);
+            continue;
+        }
+
+        if token.digits.len() > options.max_digits {
+            match &options.long_tokens {
+                LongTokens::Keep => {
+                    output.extend_from_slice(original);
+                    continue;
+                }
+                LongTokens::Auto | LongTokens::Convert => {}
+                LongTokens::Replace(marker) => {
+                    output.extend_from_slice(marker.as_bytes());
+                    continue;
+                }
+            }
+        }
+        let value = BigUint::from_str_radix(&token.digits, token.radix).unwrap();
+        let float_format = options
+            .floats
+            .iter()
+            .find(|format| token.exponent.is_none() && format.bits() == token.bits());
+        let fixed_point = options.fixed_point.as_ref().filter(|format| {
+            token.exponent.is_none() && format.bits() <= 64 && format.bits() == token.bits()
+        });
+        // The converted number if it is a real number rather than an integer.
+        let real = match (token.exponent, float_format, fixed_point) {
+            (Some(exponent), _, _) => Some(float::format_hex_float(&value, exponent, token.minus)),
+            (None, Some(format), _) => Some(format.format(&value, token.minus)),
+            (None, None, Some(format)) => Some(format.format(&value, token.minus)),
+            (None, None, None) => None,
+        };
+        let regrouping = token
+            .separator
+            .filter(|_| options.regroup)
+            .map(|separator| Grouping {
+                separator: separator.to_string(),
+                size: if options.radix == 10 { 3 } else { 4 },
+            });
+        let grouping = options.grouping.as_ref().or(regrouping.as_ref());
+        let signed = options
+            .signed
+            .and_then(|width| token.negative(&value, width));
+        let magnitude = signed.as_ref().unwrap_or(&value);
+        let negative = (signed.is_some() != token.minus) && magnitude.bits() > 0;
+        let mut converted = match &real {
+            Some(real) => real.clone(),
+            None => {
+                let mut converted = magnitude.to_str_radix(options.radix);
+                if let Some(grouping) = grouping {
+                    converted = grouping.apply(&converted);
+                }
+                if negative {
+                    converted.insert(0, '-');
+                }
+                converted
+            }
+        };
+        if real.is_none() && options.sizes.is_some() && sizes.applies(line, token.range.start) {
+            let mut size = sizes.format(magnitude.to_f64());
+            if negative {
+                size.insert(0, '-');
+            }
+            converted = if sizes.append {
+                format!("{converted} ({size})")
+            } else {
+                size
+            };
+        }
+        let hex = if token.radix == 16 {
+            Cow::Borrowed(token.digits.as_str())
+        } else {
+            Cow::Owned(value.to_str_radix(16))
+        };
+        let number = Number {
+            // The regex only matches ASCII, so this can't fail.
+            orig: std::str::from_utf8(original).unwrap(),
+            prefix: &token.prefix,
+            hex: &hex,
+            value: &value,
+            converted: &converted,
+            sizes,
+        };
+        if let Some(annotation) = &options.annotation {
+            output.extend_from_slice(original);
+            output.extend_from_slice(annotation.render(&number).as_bytes());
+            continue;
+        }
+        let mut replacement = match &options.format {
+            Some(format) => format.render(&number),
+            None => converted.clone(),
+        };
+        replacement += &token.type_suffix;
+        let width = original.len();
+        let padded = match options.padding {
+            Padding::Right => format!("{replacement:>width$}"),
+            Padding::Left => format!("{replacement:<width$}"),
+            Padding::None => replacement,
+            Padding::Bits => format!(
+                "{replacement:>width$}",
+                width = token.max_width(options.radix, grouping) + token.type_suffix.len()
+            ),
+            Padding::Borrow => {
+                overflow = replacement.chars().count().saturating_sub(width);
+                format!("{replacement:>width$}")
+            }
+        };
+        output.extend_from_slice(padded.as_bytes());
+        on_replaced(number.orig, &mut output);
+    }
+    output.extend_from_slice(borrow_spaces(&line[last_end..], overflow));
+    Cow::Owned(output)
+}
+
+/// The numbers in `line` with whether they are converted, see
+/// [`hex2dec_bytes_with`].
+fn find_tokens(
+    line: &[u8],
+    converter: &Converter,
+    literals: Option<&[Range<usize>]>,
+) -> Vec<(Token, bool)> {
+    let options = &converter.options;
+    let mut tokens: Vec<Token> = converter
+        .regex
+        .captures_iter(line)
+        .map(Token::new)
+        .collect();
+    if let Some(literals) = literals {
+        tokens.retain(|token| literals.contains(&token.range));
+    }
+    if options.negative_literals {
+        for token in &mut tokens {
+            if !token.prefix.is_empty()
+                && token.range.start > 0
+                && line[token.range.start - 1] == b'-'
+            {
+                token.range.start -= 1;
+                token.minus = true;
+            }
+        }
+    }
+    if let Some(regex) = &converter.skipped_shapes_regex {
+        for skipped in regex.find_iter(line) {
+            if !structured::is_skipped(skipped.as_bytes()) {
+                continue;
+            }
+            tokens.retain(|token| {
+                token.range.end <= skipped.start()
+                    || skipped.end() <= token.range.start
+                    || (token.digits.len() > options.max_digits
+                        && options.long_tokens != LongTokens::Auto)
+            });
+        }
+    }
+    let words: Vec<bool> = tokens.iter().map(|token| options.is_word(token)).collect();
+    let evidently_hex = tokens
+        .iter()
+        .zip(&words)
+        .map(|(token, word)| token.is_evidently_hex() && !word)
+        .collect();
+    let hex = hex_by_context(line, &tokens, evidently_hex);
+    let bare_hex = tokens
+        .iter()
+        .zip(&hex)
+        .any(|(token, hex)| *hex && token.prefix.is_empty() && token.suffix.is_empty());
+    if bare_hex {
+        // Zero-padded numbers among bare hex numbers, such as the offsets and
+        // bytes of a hex dump, are hex rather than C-style octal.
+        for token in &mut tokens {
+            if token.is_c_octal() {
+                token.radix = 16;
+            }
+        }
+    }
+
+    let converted: Vec<bool> = tokens
+        .iter()
+        .zip(hex)
+        .zip(words)
+        .map(|((token, hex), word)| {
+            !token.is_binary_literal()
+                && (token.radix != 16 || hex || (!word && options.detection == Detection::Loose))
+        })
+        .collect();
+    tokens.into_iter().zip(converted).collect()
+}
+
+/// The ranges of the numbers in `line` that `converter` converts, rather than
+/// leaves alone because they might be decimal or words.
+pub(crate) fn converted_ranges(line: &[u8], converter: &Converter) -> Vec<Range<usize>> {
+    find_tokens(line, converter, None)
+        .into_iter()
+        .filter(|(_, converted)| *converted)
+        .map(|(token, _)| token.range)
+        .collect()
+}
+
+/// Removes up to `overflow` leading spaces from `gap`, but keeps at least one.
+fn borrow_spaces(gap: &[u8], overflow: usize) -> &[u8] {
+    let spaces = gap.iter().take_while(|b| **b == b' ').count();
+    &gap[overflow.min(spaces.saturating_sub(1))..]
+}
+
+/// A number found in a line.
+struct Token {
+    /// The whole number, including any prefix.
+    range: Range<usize>,
+    radix: u32,
+    /// The digits without any prefix, suffix and separators.
+    digits: String,
+    /// The prefix that tells the radix, e.g. `0x`. Empty if none.
+    prefix: String,
+    /// The suffix that tells the radix, e.g. `h`. Empty if none.
+    suffix: String,
+    /// The binary exponent of a hex float, adjusted for the digits after the
+    /// point, e.g. -1 for `0x1.8p0`. `None` if the number isn't a hex float.
+    exponent: Option<i64>,
+    /// The type suffix, e.g. `u32`, see [`Dialect::TypeSuffixes`]. Empty if
+    /// none.
+    type_suffix: String,
+    /// The digit separator of the number, e.g. `_`, if it has any.
+    separator: Option<char>,
+    /// If a minus in front of the number is part of it, see
+    /// [`Options::negative_literals`].
+    minus: bool,
+}
+
+impl Token {
+    fn new(caps: Captures) -> Self {
+        let groups = [
+            ("bin", 2),
+            ("oct", 8),
+            ("c_oct", 8),
+            ("hex_float", 16),
+            ("hex_prefixed", 16),
+            ("hex_suffixed", 16),
+            ("hex", 16),
+        ];
+        let (radix, digits) = groups
+            .into_iter()
+            .find_map(|(name, radix)| Some((radix, caps.name(name)?)))
+            .unwrap();
+        let whole = caps.get(0).unwrap();
+        // The regex only matches ASCII, so this can't fail.
+        let text = |range: Range<usize>| {
+            let bytes = &whole.as_bytes()[range.start - whole.start()..range.end - whole.start()];
+            String::from_utf8(bytes.to_vec()).unwrap()
+        };
+        let type_suffix = caps
+            .name("type_suffix")
+            .or(caps.name("float_suffix"))
+            .map_or(whole.end()..whole.end(), |m| m.range());
+        let raw_digits = text(digits.range());
+        let exponent = caps.name("exponent").map(|exponent| {
+            let fraction_digits = raw_digits.split_once('.').map_or(0, |(_, f)| f.len());
+            let exponent = text(exponent.range());
+            // Exponents too large for an i64 are out of range of any float.
+            let out_of_range = if exponent.starts_with('-') {
+                i64::MIN
+            } else {
+                i64::MAX
+            };
+            let exponent = exponent.parse().unwrap_or(out_of_range);
+            exponent.saturating_sub(4 * fraction_digits as i64)
+        });
+        let suffix_start = caps.name("exponent").map_or(digits.end(), |e| e.end());
+        Self {
+            range: whole.range(),
+            radix,
+            digits: raw_digits.replace(['_', '\'', '.'], ""),
+            prefix: text(whole.start()..digits.start()),
+            suffix: text(suffix_start..type_suffix.start),
+            exponent,
+            type_suffix: text(type_suffix),
+            separator: raw_digits.chars().find(|c| matches!(c, '_' | '\'')),
+            minus: false,
+        }
+    }
+
+    /// The width of the largest number with as many bits as this number has
+    /// digits for, in `radix`. See [`Padding::Bits`].
+    fn max_width(&self, radix: u32, grouping: Option<&Grouping>) -> usize {
+        let max = BigUint::from_str_radix(&"1".repeat(self.bits() as usize), 2).unwrap();
+        let max = max.to_str_radix(radix);
+        grouping.map_or(max.len(), |grouping| grouping.apply(&max).chars().count())
+    }
+
+    /// The number of bits this number has digits for, e.g. 32 for 8 hex digits.
+    fn bits(&self) -> u64 {
+        self.digits.len() as u64 * u64::from(self.radix.trailing_zeros())
+    }
+
+    /// If `value`, the value of this number, is negative when interpreted as a
+    /// two's complement signed number with `width`, returns its magnitude.
+    fn negative(&self, value: &BigUint, width: SignedWidth) -> Option<BigUint> {
+        let bits = match width {
+            SignedWidth::Bits(bits) => bits,
+            SignedWidth::Inferred => self.bits(),
+        };
+        if value.bits() != bits {
+            return None;
+        }
+        BigUint::pow2(bits).checked_sub(value)
+    }
+
+    /// If the number is hex even without context, see [`Detection::Strict`].
+    fn is_evidently_hex(&self) -> bool {
+        self.radix == 16
+            && !self.is_binary_literal()
+            && (!self.prefix.is_empty()
+                || !self.suffix.is_empty()
+                || self.digits.bytes().any(|b| b.is_ascii_alphabetic()))
+    }
+
+    /// If the number is a C-style octal number such as `0755`.
+    fn is_c_octal(&self) -> bool {
+        self.radix == 8 && self.prefix.is_empty()
+    }
+
+    /// If the number is a binary literal such as `0b10`, taken for hex because
+    /// [`Options::binary`] is off. It is left alone rather than converted as
+    /// hex.
+    fn is_binary_literal(&self) -> bool {
+        self.radix == 16
+            && self.prefix.is_empty()
+            && self.digits.strip_prefix("0b").is_some_and(|bits| {
+                !bits.is_empty() && bits.bytes().all(|b| b == b'0' || b == b'1')
+            })
+    }
+}
+
+/// Returns for each token if it is hex, either because it is `evidently_hex`
+/// by itself or because it is in a whitespace separated run of equally wide
+/// tokens where at least one is, see [`Detection::Strict`].
+fn hex_by_context(line: &[u8], tokens: &[Token], evidently_hex: Vec<bool>) -> Vec<bool> {
+    let mut hex = evidently_hex;
+    let mut run_start = 0;
+    for run_end in 1..=tokens.len() {
+        let run_continues = tokens.get(run_end).is_some_and(|next| {
+            let prev = &tokens[run_end - 1];
+            let gap = &line[prev.range.end..next.range.start];
+            !gap.is_empty()
+                && gap.iter().all(u8::is_ascii_whitespace)
+                && prev.range.len() == next.range.len()
+        });
+        if !run_continues {
+            let run = &mut hex[run_start..run_end];
+            if run.contains(&true) {
+                run.fill(true);
+            }
+            run_start = run_end;
+        }
+    }
+    hex
+}
+
+/// Converts decimal numbers in `line` to hex notation in place. The reverse of
+/// [`hex2dec_line`].
+pub fn dec2hex_line(line: &str, options: &Dec2HexOptions) -> String {
+    String::from_utf8(dec2hex_bytes(line.as_bytes(), options).into_owned()).unwrap()
+}
+
+/// Like [`dec2hex_line`] but for lines that might not be valid UTF-8.
+pub fn dec2hex_bytes<'a>(line: &'a [u8], options: &Dec2HexOptions) -> Cow<'a, [u8]> {
+    DEC_REGEX.replace_all(line, |caps: &Captures| {
+        let m = caps.get(0).unwrap();
+        // The regex only matches ASCII digits, so this can't fail.
+        let dec = std::str::from_utf8(m.as_bytes()).unwrap();
+        let mut hex = BigUint::from_str_radix(dec, 10).unwrap().to_str_radix(16);
+        if options.case == Case::Upper {
+            hex.make_ascii_uppercase();
+        }
+        format!("{:>width$}", options.prefix.clone() + &hex, width = m.len()).into_bytes()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex2dec_line() {
+        let tests = [
+            ("", ""),
+            (" a ", " a "),
+            (" 1 ", " 1 "),
+            ("0x1", "0x1"),
+            ("0x12", "  18"),
+            ("took 10 ms, 0x10 bytes", "took 10 ms,   16 bytes"),
+            ("99 bottles, 5b bottles", "99 bottles, 91 bottles"),
+            ("  0x1  ", "  0x1  "),
+            ("  0x1  ", "  0x1  "),
+            (
+                "  Magic:   7f 45 4c 46 02 01 01 00 00 00 00 00 00 00 00 00 ",
+                "  Magic:   127 69 76 70  2  1  1  0  0  0  0  0  0  0  0  0 ",
+            ),
+            (
+                "        Entry point address:               0x5a200",
+                "        Entry point address:                369152",
+            ),
+            (
+                "u128::MAX 0xffffffffffffffffffffffffffffffff",
+                "u128::MAX 340282366920938463463374607431768211455",
+            ),
+            (
+                "sha1 0x0123456789abcdef0123456789abcdef01234567",
+                "sha1 6495562832581790663061892574634853316331521383",
+            ),
+            (
+                "uint256 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "uint256 115792089237316195423570985008687907853269984665640564039457584007913129639935",
+            ),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.0, &Options::default()), test.1);
+        }
+    }
+
+    #[test]
+    fn test_long_tokens() {
+        let line = "commit 0123456789abcdef0123456789abcdef01234567 at 0x5a200";
+        let tests = [
+            (
+                LongTokens::Keep,
+                "commit 0123456789abcdef0123456789abcdef01234567 at  369152",
+            ),
+            (
+                LongTokens::Convert,
+                "commit 6495562832581790663061892574634853316331521383 at  369152",
+            ),
+            (
+                LongTokens::Replace("<sha1>".to_owned()),
+                "commit <sha1> at  369152",
+            ),
+        ];
+        for test in tests {
+            let options = Options {
+                max_digits: 16,
+                long_tokens: test.0,
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(line, &options), test.1);
+        }
+    }
+
+    #[test]
+    fn test_long_tokens_and_hashes() {
+        let line = "commit 3e1b8c9f4a5d6e7f8091a2b3c4d5e6f708192a3b at 0x5a200";
+        let tests = [
+            (
+                LongTokens::Auto,
+                "commit 3e1b8c9f4a5d6e7f8091a2b3c4d5e6f708192a3b at  369152",
+            ),
+            (
+                LongTokens::Keep,
+                "commit 3e1b8c9f4a5d6e7f8091a2b3c4d5e6f708192a3b at  369152",
+            ),
+            (
+                LongTokens::Convert,
+                "commit 354571797835213160653773301239479003658292111931 at  369152",
+            ),
+            (
+                LongTokens::Replace("<sha1>".to_owned()),
+                "commit <sha1> at  369152",
+            ),
+        ];
+        for test in tests {
+            let options = Options {
+                long_tokens: test.0,
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(line, &options), test.1);
+        }
+        // Below max_digits, the hash shape applies whatever the policy.
+        let options = Options {
+            max_digits: 64,
+            long_tokens: LongTokens::Convert,
+            ..Options::default()
+        };
+        assert_eq!(
+            hex2dec_line(line, &options),
+            line.replace("0x5a200", " 369152")
+        );
+    }
+
+    #[test]
+    fn test_hex2dec_bytes() {
+        let tests: [(&[u8], &[u8]); 3] = [
+            (b"\xff", b"\xff"),
+            (b"caf\xe9 0x10", b"3247\xe9   16"),
+            (b"\x00\x01 ff\xfe\xc0 ab", b"\x00\x01 255\xfe\xc0 171"),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_bytes(test.0, &Options::default()), test.1);
+        }
+    }
+
+    #[test]
+    fn test_converter() {
+        let options = Options {
+            binary: true,
+            ..Options::default()
+        };
+        let converter = Converter::new(options.clone());
+        for line in [
+            "0x10 0b11",
+            "2024-10-17 0xff",
+            "took 10 ms",
+            "caf\u{e9} 0x10",
+        ] {
+            assert_eq!(converter.hex2dec_line(line), hex2dec_line(line, &options));
+        }
+    }
+
+    #[test]
+    fn test_transform_stream() {
+        let tests: [(&[u8], &[u8]); 4] = [
+            (b"", b""),
+            (b"ff\nff", b"255\n255"),
+            (b"0x10\r\nab\r\n", b"  16\r\n171\r\n"),
+            (b"\n\r\n\n", b"\n\r\n\n"),
+        ];
+        for test in tests {
+            let mut output = vec![];
+            let options = Options::default();
+            transform_stream(test.0, &mut output, |line| {
+                hex2dec_bytes(line, &options).into_owned()
+            })
+            .unwrap();
+            assert_eq!(output, test.1);
+        }
+    }
+
+    #[test]
+    fn test_dec2hex_line() {
+        let lower = Dec2HexOptions::default();
+        let upper = Dec2HexOptions {
+            prefix: String::new(),
+            case: Case::Upper,
+        };
+        let tests = [
+            ("", "", ""),
+            (" 1 ", " 1 ", " 1 "),
+            ("0x10", "0x10", "0x10"),
+            ("  255", "  0xff", "   FF"),
+            ("took 10 ms", "took 0xa ms", "took  A ms"),
+            (
+                "        Entry point address:                369152",
+                "        Entry point address:                0x5a200",
+                "        Entry point address:                 5A200",
+            ),
+            (
+                "340282366920938463463374607431768211455",
+                "     0xffffffffffffffffffffffffffffffff",
+                "       FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            ),
+        ];
+        for test in tests {
+            assert_eq!(dec2hex_line(test.0, &lower), test.1);
+            assert_eq!(dec2hex_line(test.0, &upper), test.2);
+        }
+    }
+
+    #[test]
+    fn test_binary_and_octal() {
+        let binary = Options {
+            binary: true,
+            ..Options::default()
+        };
+        let octal = Options {
+            octal: true,
+            ..Options::default()
+        };
+        let loose = Options {
+            detection: Detection::Loose,
+            ..Options::default()
+        };
+        let tests = [
+            (&binary, "0b1010_0001", "        161"),
+            (&binary, "0b2 0b_1", "178 0b_1"),
+            (&octal, "0o755 0o7_5_5", "  493     493"),
+            (&octal, "0755 0789", " 493 0789"),
+            (&octal, "010 011 00a", " 16  17  10"),
+            (&octal, "00000010  03 00 3e 00", "00000010   3  0 62  0"),
+            (&octal, "mode 0755 at 0x10", "mode  493 at   16"),
+            (&Options::default(), "0b10 0o10 010", "0b10 0o10 010"),
+            (&loose, "0b10 0b12 0x10", "0b10 2834   16"),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_radix() {
+        let tests = [
+            (10, "mode 0x1ed", "mode   493"),
+            (8, "mode 0x1ed", "mode   755"),
+            (2, "ctrl 0x5a", "ctrl 1011010"),
+            (16, "0x5a200", "  5a200"),
+            (36, "0xffff", "  1ekf"),
+        ];
+        for test in tests {
+            let options = Options {
+                radix: test.0,
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.1, &options), test.2);
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        let tests = [
+            ("took 10 ms", "took 10 ms", "took 16 ms"),
+            ("00 0a 10 ff", " 0 10 16 255", " 0 10 16 255"),
+            ("00 10 ff0", "00 10 4080", " 0 16 4080"),
+            ("10 0x10", "10   16", "16   16"),
+            ("2024 v1.10.3", "2024 v1.10.3", "8228 v1.10.3"),
+        ];
+        for test in tests {
+            let loose = Options {
+                detection: Detection::Loose,
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.0, &Options::default()), test.1);
+            assert_eq!(hex2dec_line(test.0, &loose), test.2);
+        }
+    }
+
+    #[test]
+    fn test_english_words() {
+        let allowlist = Options {
+            word_allowlist: vec!["fee".to_owned(), "C0DE".to_owned()],
+            word_denylist: vec!["Cafe".to_owned()],
+            ..Options::default()
+        };
+        let loose = Options {
+            detection: Detection::Loose,
+            ..Options::default()
+        };
+        let tests = [
+            (&Options::default(), "a bad decade", "a bad decade"),
+            (&Options::default(), "Face Feed 0xface", "Face Feed  64206"),
+            (
+                &Options::default(),
+                "0000: 7f45 dead beef",
+                "0000: 32581 57005 48879",
+            ),
+            (&Options::default(), "dead beef", "dead beef"),
+            (&allowlist, "fee cafe, c0de", "fee 51966, c0de"),
+            (&loose, "add 10 to bad", "add 16 to bad"),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_skipped_shapes() {
+        let loose = Options {
+            detection: Detection::Loose,
+            ..Options::default()
+        };
+        let no_skipped_shapes = Options {
+            skipped_shapes: vec![],
+            ..loose.clone()
+        };
+        let tests = [
+            "2024-10-17 12:34:56.789 0x10",
+            "v1.10.3 1.10.3-rc1+ab 0x10",
+            "from 192.168.10.10:22 0x10",
+            "route 10.10.0.0/16 0x10",
+            "12:34 0x10",
+            "550e8400-e29b-41d4-a716-446655440000 0x10",
+            "link/ether 52:54:00:ab:cd:ef brd ff:ff:ff:ff:ff:ff 0x10",
+            "inet6 fe80::5054:ff:feab:cdef/64 0x10",
+            "inet6 fe80::/10 2001:db8::ff00:42:8329 0x10",
+            "inet6 2001:0db8:85a3:0000:0000:8a2e:0370:7334 0x10",
+            "commit 3e1b8c9f4a5d6e7f8091a2b3c4d5e6f708192a3b 0x10",
+            "sha256 e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855 0x10",
+        ];
+        for test in tests {
+            let unchanged = test.strip_suffix(" 0x10").unwrap();
+            assert_eq!(hex2dec_line(test, &loose), format!("{unchanged}   16"));
+        }
+        assert_eq!(hex2dec_line("ns::ff10 ab::", &loose), "ns::65296 171::");
+        assert_eq!(
+            hex2dec_line("2024-10-17 v1.10.3", &no_skipped_shapes),
+            "8228-16-23 v1.16.3"
+        );
+    }
+
+    #[test]
+    fn test_annotation() {
+        let tests = [
+            (" ({value})", "at 0x5a200.", "at 0x5a200 (369152)."),
+            (" ({value})", "7f 45", "7f (127) 45 (69)"),
+            ("={value}", "took 10 ms", "took 10 ms"),
+            ("", "0xff", "0xff"),
+        ];
+        for test in tests {
+            let options = Options {
+                annotation: Some(test.0.parse().unwrap()),
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.1, &options), test.2);
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        let tests = [
+            ("{dec}/{hex}", "at 0x5a200.", "at 369152/5a200."),
+            ("{dec}", "at 0x1f.", "at   31."),
+            ("{hex}", "at 0x00FF.", "at   00FF."),
+            ("<{orig}>", "7f 45", "<7f> <45>"),
+            ("{size}", "len 0x5a200", "len 360.5 KiB"),
+        ];
+        for test in tests {
+            let options = Options {
+                format: Some(test.0.parse().unwrap()),
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.1, &options), test.2);
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        let tests = [
+            (Padding::Right, "|0x12|ff|", "|  18|255|"),
+            (Padding::Left, "|0x12|ff|", "|18  |255|"),
+            (Padding::None, "|0x12|ff|", "|18|255|"),
+            (Padding::Bits, "|0x12|ff|", "| 18|255|"),
+            (
+                Padding::Bits,
+                "|0x0000000000000012|",
+                "|                  18|",
+            ),
+            (Padding::Borrow, "|ff   ff   x|", "|255  255  x|"),
+            (Padding::Borrow, "|ffff ff x|", "|65535 255 x|"),
+            (Padding::Borrow, "|0x12 ff  |", "|  18 255 |"),
+        ];
+        for test in tests {
+            let options = Options {
+                padding: test.0,
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.1, &options), test.2);
+        }
+
+        let grouped = Options {
+            padding: Padding::Bits,
+            grouping: Some(Grouping::default()),
+            ..Options::default()
+        };
+        assert_eq!(
+            hex2dec_line("|0x0000000000000010|0xffffffffffffffff|", &grouped),
+            "|                        16|18,446,744,073,709,551,615|"
+        );
+        let regrouped = Options {
+            padding: Padding::Bits,
+            regroup: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            hex2dec_line("|0x0000_0010|0xffff_ffff|", &regrouped),
+            "|           16|4_294_967_295|"
+        );
+    }
+
+    #[test]
+    fn test_grouping() {
+        let tests = [
+            (",", 3, "0x7ffffffff000", "140,737,488,351,232"),
+            ("_", 3, "at 0x0000000000001000.", "at              4_096."),
+            ("'", 3, "0xff", " 255"),
+            ("\u{2009}", 3, "0x100000", "1\u{2009}048\u{2009}576"),
+            (",", 4, "0x100000", "104,8576"),
+        ];
+        for test in tests {
+            let options = Options {
+                grouping: Some(Grouping {
+                    separator: test.0.to_owned(),
+                    size: test.1,
+                }),
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.2, &options), test.3);
+        }
+        assert_eq!(Grouping::from_locale("de_DE.UTF-8").separator, ".");
+        assert_eq!(Grouping::from_locale("de_CH").separator, "'");
+        assert_eq!(Grouping::from_locale("fr_FR").separator, "\u{2009}");
+        assert_eq!(Grouping::from_locale("C").separator, ",");
+    }
+
+    #[test]
+    fn test_sizes() {
+        let append = Sizes {
+            append: true,
+            ..Sizes::default()
+        };
+        let si = Sizes {
+            units: Units::Si,
+            precision: 2,
+            ..Sizes::default()
+        };
+        let keywords = Sizes {
+            keywords: vec!["size".to_owned(), "len".to_owned()],
+            ..Sizes::default()
+        };
+        let tests = [
+            (&Sizes::default(), "0x5a200", "360.5 KiB"),
+            (&Sizes::default(), "0x2a 0x100000", "42 B  1.0 MiB"),
+            (&append, "0x5a200", "369152 (360.5 KiB)"),
+            (&si, "0x5a200", "369.15 kB"),
+            (
+                &keywords,
+                "addr=0x5a200 size=0x5a200 sh_size: 0x400 Length 0x10",
+                "addr= 369152 size=360.5 KiB sh_size: 1.0 KiB Length 16 B",
+            ),
+        ];
+        for test in tests {
+            let options = Options {
+                sizes: Some(test.0.clone()),
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.1, &options), test.2);
+        }
+    }
+
+    #[test]
+    fn test_signed() {
+        let tests = [
+            (
+                SignedWidth::Bits(64),
+                "0xfffffffffffffff2",
+                "               -14",
+            ),
+            (
+                SignedWidth::Bits(64),
+                "0x7ffffffffffffff2",
+                "9223372036854775794",
+            ),
+            (SignedWidth::Bits(32), "rc=0xfffffff2", "rc=       -14"),
+            (SignedWidth::Bits(32), "0xfffffffffff2", "281474976710642"),
+            (SignedWidth::Bits(8), "80 7f ff", "-128 127 -1"),
+            (
+                SignedWidth::Inferred,
+                "0xfff2 0x00f2 f2",
+                "   -14    242 -14",
+            ),
+        ];
+        for test in tests {
+            let options = Options {
+                signed: Some(test.0),
+                ..Options::default()
+            };
+            assert_eq!(hex2dec_line(test.1, &options), test.2);
+        }
+
+        let sizes = Options {
+            signed: Some(SignedWidth::Bits(8)),
+            sizes: Some(Sizes {
+                append: true,
+                ..Sizes::default()
+            }),
+            ..Options::default()
+        };
+        assert_eq!(hex2dec_line("0xf2 0x72", &sizes), "-14 (-14 B) 114 (114 B)");
+    }
+
+    #[test]
+    fn test_negative_literals() {
+        let negative = Options {
+            negative_literals: true,
+            ..Options::default()
+        };
+        let tests = [
+            (&Options::default(), "[rbp-0x18]", "[rbp-  24]"),
+            (&negative, "[rbp-0x18]", "[rbp  -24]"),
+            (&negative, "off -0x10, -ff", "off   -16, -255"),
+            (&negative, "-0x00 - 0x01", "    0 -    1"),
+            (
+                &Options {
+                    padding: Padding::None,
+                    ..negative.clone()
+                },
+                "mov [rbp-0x18], eax",
+                "mov [rbp-24], eax",
+            ),
+            (
+                &Options {
+                    signed: Some(SignedWidth::Bits(8)),
+                    ..negative.clone()
+                },
+                "-0xf2",
+                "   14",
+            ),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_dialects() {
+        let all = Options {
+            dialects: Dialect::ALL.to_vec(),
+            ..Options::default()
+        };
+        let tests = [
+            (&Options::default(), "0X1F 0xdead_beef", "  31  3735928559"),
+            (
+                &Options::default(),
+                "0xdead'beef 0xdead_beef",
+                " 3735928559  3735928559",
+            ),
+            (
+                &Options::default(),
+                "$FF #xFF &HFF 0FFh",
+                "$255 #xFF &HFF 0FFh",
+            ),
+            (&all, "$FF #xFF &HFF 0FFh", "255  255  255  255"),
+            (&all, "8'hFF 'hff 4'sHf0", "  255  255    240"),
+            (&all, "a$FF 24h 0x_1", "a$255  36 0x_1"),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_type_suffixes() {
+        let regroup = Options {
+            regroup: true,
+            ..Options::default()
+        };
+        let tests = [
+            (
+                &Options::default(),
+                "0x10UL 0xffu8 0xFF_i64",
+                "  16UL  255u8  255_i64",
+            ),
+            (
+                &Options::default(),
+                "0xFFFF_FFFFu32 0x10user",
+                " 4294967295u32 0x10user",
+            ),
+            (
+                &regroup,
+                "0xFFFF_FFFFu32 0x10ull",
+                "4_294_967_295u32   16ull",
+            ),
+            (&regroup, "0xdead'beef", "3'735'928'559"),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_floats() {
+        let floats = Options {
+            floats: vec![FloatFormat::F32, FloatFormat::F64],
+            negative_literals: true,
+            ..Options::default()
+        };
+        let unpadded = Options {
+            padding: Padding::None,
+            ..Options::default()
+        };
+        let tests = [
+            (&Options::default(), "0x1.8p3 0x.8p-1f", "   12.0    0.25f"),
+            (
+                &unpadded,
+                "0x1.8p-1074 0x10p-1075 0x1000000p-1090 0x1p-1076",
+                "1e-323 4e-323 1.265e-321 0.0",
+            ),
+            (
+                &unpadded,
+                "0x1.0000000000000800000001p0 0x1.fffffffffffff8p1023",
+                "1.0000000000000002 inf",
+            ),
+            (&Options::default(), "0x10.8p3 0X1P-2", "   132.0   0.25"),
+            (
+                &floats,
+                "0x3fc00000 0x3ff8000000000000",
+                "       1.5                1.5",
+            ),
+            (&floats, "-0x3fc00000 0x0001", "       -1.5      1"),
+            (&floats, "0xbf800000 0x3dcccccd", "      -1.0        0.1"),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let q15 = Options {
+            fixed_point: Some("Q15".parse().unwrap()),
+            ..Options::default()
+        };
+        let q16_16 = Options {
+            fixed_point: Some(FixedPoint {
+                precision: Some(2),
+                .."Q16.16".parse().unwrap()
+            }),
+            ..Options::default()
+        };
+        let q40_40 = Options {
+            fixed_point: Some(FixedPoint {
+                signed: true,
+                integer_bits: 40,
+                fraction_bits: 40,
+                precision: None,
+            }),
+            ..Options::default()
+        };
+        let tests = [
+            (&q40_40, "0x00000000010000000000", "         1099511627776"),
+            (
+                &q15,
+                "gain 0x4000 0xc000 0x10",
+                "gain 0.50000 -0.50000   16",
+            ),
+            (
+                &q16_16,
+                "temp 0x00178000 0xfffe4000",
+                "temp      23.50      -1.75",
+            ),
+        ];
+        for test in tests {
+            assert_eq!(hex2dec_line(test.1, test.0), test.2);
+        }
+    }
+}
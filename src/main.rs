@@ -1,54 +1,379 @@
-use once_cell::sync::Lazy;
-use regex::{Captures, Regex};
+use hex2dec::{dec2hex_bytes, transform_stream};
+use hex2dec::{hex2dec_source, hex2dec_table, hex2dec_table_stream};
+use hex2dec::{Case, Converter, Dec2HexOptions, Grouping, Options, Sizes, SourceOptions};
+use std::ffi::OsString;
+use std::io::{Read, Write};
 
-static REGEX: Lazy<Regex> = Lazy::new(|| regex::Regex::new(r"\b(0x)?([0-9a-fA-F]{2,})\b").unwrap());
+const USAGE: &str = "\
+Usage: hex2dec [OPTIONS] [ARGS]...
 
-fn main() {
-    for line in std::io::stdin().lines() {
-        let line = line.unwrap();
-        println!("{}", hex2dec_line(&line));
+Transforms each ARG, or stdin if there are no ARGs, with hex numbers converted
+to decimal notation in place. Stdin does not need to be valid UTF-8; all bytes
+except hex numbers, including line endings, are passed through unchanged.
+
+Options:
+  --dec2hex                  Convert decimal numbers to hex instead
+  --prefix <PREFIX>          Prefix of hex numbers with --dec2hex [default: 0x]
+  --upper                    Use uppercase hex digits with --dec2hex
+  --detect <MODE>            Which hex numbers to convert [default: strict]
+                             strict: only with 0x, a-f, or in a hex dump
+                             loose: any run of at least two hex digits
+  --convert-words            Also convert English words such as bad and cafe
+  --keep-word <WORD>         Leave WORD alone like an English word
+  --convert-word <WORD>      Convert WORD even if it is an English word
+  --convert-shape <SHAPE>    Convert numbers in SHAPE instead of leaving it alone
+                             uuid, ipv6, mac, ipv4, version, date, time, or hash
+  --dialect <DIALECT>        Also recognize hex numbers in DIALECT
+                             dollar ($FF), lisp (#xFF), basic (&HFF), verilog
+                             (8'hFF), or asm-suffix (0FFh)
+  --no-dialect <DIALECT>     Don't recognize hex numbers in DIALECT
+                             upper-x (0XFF), underscores (0xdead_beef),
+                             apostrophes (0xdead'beef), or type-suffixes
+                             (0xffu8 and 0x10UL), or hex-floats (0x1.8p3)
+  --binary                   Also convert binary numbers such as 0b1010_0001
+  --octal                    Also convert octal numbers such as 0o755 and 0755
+  --radix <RADIX>            Radix to convert numbers to, from 2 to 36 [default: 10]
+  --tables                   Realign columns after conversion so tables still line
+                             up. Blocks of lines are separated by blank lines
+  --source <LANGUAGE>        Only convert numeric literals in source code, not
+                             identifiers, comments or strings. c, rust, or python
+  --comment-original         Add the original literal as a comment with --source
+  --negative                 Convert a minus directly before a number with a
+                             prefix as part of it, e.g. -0x18 to -24
+  --signed <BITS>            Interpret numbers as two's complement signed numbers
+                             with BITS bits, e.g. 8, 16, 32, 64, 128, or auto
+                             for as many bits as the number has digits for
+  --float <FORMAT>           Interpret numbers with as many bits as FORMAT as its
+                             bit pattern, e.g. 0x3fc00000 as f32 is 1.5. f16,
+                             bf16, f32, or f64. Can be given more than once
+  --fixed-point <FORMAT>     Interpret numbers with as many bits as FORMAT as
+                             fixed-point numbers in Q notation, e.g. Q15, Q16.16,
+                             or UQ8.8 for unsigned
+  --fixed-precision <N>      Number of decimals of fixed-point numbers [default:
+                             just enough to tell apart adjacent values]
+  --size <STYLE>             Render converted numbers as human-readable sizes
+                             replace (360.5 KiB) or append (369152 (360.5 KiB))
+  --size-units <UNITS>       iec (KiB, MiB, ...) or si (kB, MB, ...) [default: iec]
+  --size-precision <N>       Number of decimals of sizes [default: 1]
+  --size-keyword <WORD>      Only render numbers after WORD as sizes, e.g. size
+                             (not with --tables)
+  --group <SEPARATOR>        Group digits of converted numbers with SEPARATOR
+                             e.g. , _ ' thin (a thin space), or locale
+  --group-size <N>           Digits per group [default: 3]
+  --regroup                  Group digits of converted numbers with the separator
+                             of the original number, e.g. 0xFFFF_FFFF to
+                             4_294_967_295
+  --padding <PADDING>        How to pad converted numbers [default: right]
+                             right, left, none, bits (as wide as the largest
+                             number with as many bits), or borrow (remove
+                             spaces after wider numbers to keep columns)
+  --format <TEMPLATE>        Replace numbers with TEMPLATE [default: {value}]
+  --annotate                 Keep numbers and append the converted number
+  --annotation <TEMPLATE>    Keep numbers and append TEMPLATE [default: ' ({value})']
+  --max-digits <N>           Hex numbers with more than N digits are long [default: 32]
+  --long-tokens <POLICY>     What to do with long hex numbers [default: auto]
+                             auto (convert unless shaped like a hash), keep,
+                             convert, or replace[=MARKER], which also apply
+                             to long hashes
+  -h, --help                 Print help
+
+Placeholders in TEMPLATE:
+  {value}                    The converted number
+  {dec}                      The number in decimal
+  {orig}                     The number as it was found, e.g. 0x5a200
+  {hex}                      The bare hex digits as found, e.g. 00FF for 0x00FF
+  {prefix}                   The prefix of the number as it was found, e.g. 0x
+  {bits}                     The number of significant bits of the number
+  {size}                     The number as a human-readable size, e.g. 360.5 KiB
+";
+
+/// Options and positional args from the command line.
+#[derive(Debug, Default)]
+struct Cli {
+    options: Options,
+    dec2hex: Option<Dec2HexOptions>,
+    tables: bool,
+    source: Option<SourceOptions>,
+    /// The args as bytes, as they might not be valid UTF-8.
+    args: Vec<Vec<u8>>,
+}
+
+impl Cli {
+    fn parse(mut args: impl Iterator<Item = OsString>) -> Result<Self, String> {
+        let mut cli = Self::default();
+        let mut prefix = None;
+        let mut upper = false;
+        // The first option given that only applies to hex2dec.
+        let mut hex2dec_option = None;
+        let mut comment_original = false;
+        let mut fixed_precision = None;
+        while let Some(arg) = args.next() {
+            let arg = match arg.into_string() {
+                Ok(arg) => arg,
+                Err(arg) => {
+                    cli.args.push(arg.into_encoded_bytes());
+                    continue;
+                }
+            };
+            if arg == "--" {
+                cli.args
+                    .extend(args.by_ref().map(OsString::into_encoded_bytes));
+                break;
+            }
+            if arg == "-h" || arg == "--help" {
+                print!("{USAGE}");
+                std::process::exit(0);
+            }
+            let Some(option) = arg.strip_prefix("--") else {
+                cli.args.push(arg.into_bytes());
+                continue;
+            };
+            let (name, mut value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_owned())),
+                None => (option, None),
+            };
+            let mut value = || match value.take() {
+                Some(value) => Ok(value),
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("missing value for --{name}"))?
+                    .into_string()
+                    .map_err(|_| format!("invalid UTF-8 in value for --{name}")),
+            };
+            match name {
+                "dec2hex" => {
+                    cli.dec2hex.get_or_insert_with(Dec2HexOptions::default);
+                }
+                "prefix" => prefix = Some(value()?),
+                "upper" => upper = true,
+                "detect" => cli.options.detection = value()?.parse()?,
+                "convert-words" => cli.options.english_words = false,
+                "keep-word" => cli.options.word_allowlist.push(value()?),
+                "convert-word" => cli.options.word_denylist.push(value()?),
+                "convert-shape" => {
+                    let shape = value()?.parse()?;
+                    cli.options.skipped_shapes.retain(|s| *s != shape);
+                }
+                "dialect" => {
+                    let dialect = value()?.parse()?;
+                    if !cli.options.dialects.contains(&dialect) {
+                        cli.options.dialects.push(dialect);
+                    }
+                }
+                "no-dialect" => {
+                    let dialect = value()?.parse()?;
+                    cli.options.dialects.retain(|d| *d != dialect);
+                }
+                "binary" => cli.options.binary = true,
+                "octal" => cli.options.octal = true,
+                "radix" => {
+                    let value = value()?;
+                    cli.options.radix = value
+                        .parse()
+                        .ok()
+                        .filter(|radix| (2..=36).contains(radix))
+                        .ok_or_else(|| format!("invalid radix: {value}"))?;
+                }
+                "tables" => cli.tables = true,
+                "source" => {
+                    cli.source = Some(SourceOptions {
+                        language: value()?.parse()?,
+                        comments: comment_original,
+                    })
+                }
+                "comment-original" => comment_original = true,
+                "negative" => cli.options.negative_literals = true,
+                "signed" => cli.options.signed = Some(value()?.parse()?),
+                "float" => cli.options.floats.push(value()?.parse()?),
+                "fixed-point" => cli.options.fixed_point = Some(value()?.parse()?),
+                "fixed-precision" => {
+                    let value = value()?;
+                    fixed_precision = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid precision: {value}"))?,
+                    );
+                }
+                "size" => {
+                    cli.options.sizes.get_or_insert_with(Sizes::default).append =
+                        match value()?.as_str() {
+                            "replace" => false,
+                            "append" => true,
+                            style => return Err(format!("invalid size style: {style}")),
+                        };
+                }
+                "size-units" => {
+                    cli.options.sizes.get_or_insert_with(Sizes::default).units =
+                        value()?.parse()?;
+                }
+                "size-precision" => {
+                    let value = value()?;
+                    cli.options
+                        .sizes
+                        .get_or_insert_with(Sizes::default)
+                        .precision = value
+                        .parse()
+                        .map_err(|_| format!("invalid precision: {value}"))?;
+                }
+                "size-keyword" => {
+                    let sizes = cli.options.sizes.get_or_insert_with(Sizes::default);
+                    sizes.keywords.push(value()?);
+                }
+                "group" => {
+                    let separator = match value()?.as_str() {
+                        "thin" => "\u{2009}".to_owned(),
+                        "locale" => Grouping::from_locale(&locale()).separator,
+                        separator => separator.to_owned(),
+                    };
+                    cli.options
+                        .grouping
+                        .get_or_insert_with(Grouping::default)
+                        .separator = separator;
+                }
+                "group-size" => {
+                    let value = value()?;
+                    cli.options
+                        .grouping
+                        .get_or_insert_with(Grouping::default)
+                        .size = value
+                        .parse()
+                        .ok()
+                        .filter(|size| *size > 0)
+                        .ok_or_else(|| format!("invalid group size: {value}"))?;
+                }
+                "regroup" => cli.options.regroup = true,
+                "padding" => cli.options.padding = value()?.parse()?,
+                "format" => cli.options.format = Some(value()?.parse()?),
+                "annotate" => {
+                    cli.options
+                        .annotation
+                        .get_or_insert_with(|| " ({value})".parse().unwrap());
+                }
+                "annotation" => cli.options.annotation = Some(value()?.parse()?),
+                "max-digits" => {
+                    let value = value()?;
+                    cli.options.max_digits = value
+                        .parse()
+                        .map_err(|_| format!("invalid number of digits: {value}"))?;
+                }
+                "long-tokens" => cli.options.long_tokens = value()?.parse()?,
+                _ => return Err(format!("unknown option: --{name}")),
+            }
+            if !matches!(name, "dec2hex" | "prefix" | "upper") {
+                hex2dec_option.get_or_insert_with(|| name.to_owned());
+            }
+        }
+        match &mut cli.dec2hex {
+            Some(dec2hex) => {
+                if let Some(prefix) = prefix {
+                    dec2hex.prefix = prefix;
+                }
+                if upper {
+                    dec2hex.case = Case::Upper;
+                }
+            }
+            None if prefix.is_some() => return Err("--prefix requires --dec2hex".to_owned()),
+            None if upper => return Err("--upper requires --dec2hex".to_owned()),
+            None => {}
+        }
+        if cli.tables && cli.dec2hex.is_some() {
+            return Err("--tables can't be used with --dec2hex".to_owned());
+        }
+        if cli.tables
+            && cli
+                .options
+                .sizes
+                .as_ref()
+                .is_some_and(|sizes| !sizes.keywords.is_empty())
+        {
+            return Err("--size-keyword can't be used with --tables".to_owned());
+        }
+        match &mut cli.options.fixed_point {
+            Some(fixed_point) => fixed_point.precision = fixed_precision,
+            None if fixed_precision.is_some() => {
+                return Err("--fixed-precision requires --fixed-point".to_owned());
+            }
+            None => {}
+        }
+        if cli.source.is_some() && (cli.tables || cli.dec2hex.is_some()) {
+            return Err("--source can't be used with --tables or --dec2hex".to_owned());
+        }
+        match &mut cli.source {
+            Some(source) => source.comments = comment_original,
+            None if comment_original => {
+                return Err("--comment-original requires --source".to_owned());
+            }
+            None => {}
+        }
+        if let (Some(_), Some(option)) = (&cli.dec2hex, hex2dec_option) {
+            return Err(format!("--{option} can't be used with --dec2hex"));
+        }
+        Ok(cli)
     }
 }
 
-fn hex2dec_line(line: &str) -> String {
-    REGEX
-        .replace_all(line, |caps: &Captures| {
-            let m = caps.get(0).unwrap();
-            let hex = caps.get(2).unwrap().as_str();
-            format!(
-                "{:width$}",
-                u128::from_str_radix(hex, 16).unwrap(),
-                width = m.len()
-            )
-        })
-        .into_owned()
+/// The locale for numbers, from the environment variables POSIX specifies.
+fn locale() -> String {
+    ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default()
+}
+
+/// Like `println!`, but for lines that might not be valid UTF-8.
+fn println_bytes(line: &[u8]) {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(line).unwrap();
+    stdout.write_all(b"\n").unwrap();
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_hex2dec_line() {
-        let tests = [
-            ("", ""),
-            (" a ", " a "),
-            (" 1 ", " 1 "),
-            ("0x1", "0x1"),
-            ("0x12", "  18"),
-            ("  0x1  ", "  0x1  "),
-            ("  0x1  ", "  0x1  "),
-            (
-                "  Magic:   7f 45 4c 46 02 01 01 00 00 00 00 00 00 00 00 00 ",
-                "  Magic:   127 69 76 70  2  1  1  0  0  0  0  0  0  0  0  0 ",
-            ),
-            (
-                "        Entry point address:               0x5a200",
-                "        Entry point address:                369152",
-            ),
-        ];
-        for test in tests {
-            assert_eq!(hex2dec_line(test.0), test.1);
+fn main() {
+    let cli = Cli::parse(std::env::args_os().skip(1)).unwrap_or_else(|e| {
+        eprint!("error: {e}\n\n{USAGE}");
+        std::process::exit(2);
+    });
+    if let Some(source_options) = &cli.source {
+        if cli.args.is_empty() {
+            let mut source = vec![];
+            std::io::stdin().lock().read_to_end(&mut source).unwrap();
+            let mut stdout = std::io::stdout().lock();
+            stdout
+                .write_all(&hex2dec_source(&source, source_options, &cli.options))
+                .unwrap();
+            stdout.flush().unwrap();
+        } else {
+            for arg in &cli.args {
+                println_bytes(&hex2dec_source(arg, source_options, &cli.options));
+            }
+        }
+    } else if cli.tables {
+        if cli.args.is_empty() {
+            let stdin = std::io::stdin().lock();
+            let stdout = std::io::stdout().lock();
+            hex2dec_table_stream(stdin, stdout, &cli.options).unwrap();
+        } else {
+            let lines: Vec<&[u8]> = cli.args.iter().map(Vec::as_slice).collect();
+            for line in hex2dec_table(&lines, &cli.options) {
+                println_bytes(&line);
+            }
+        }
+    } else if cli.args.is_empty() {
+        let stdin = std::io::stdin().lock();
+        let stdout = std::io::stdout().lock();
+        let converter = Converter::new(cli.options.clone());
+        transform_stream(stdin, stdout, |line| match &cli.dec2hex {
+            Some(options) => dec2hex_bytes(line, options).into_owned(),
+            None => converter.hex2dec_bytes(line).into_owned(),
+        })
+        .unwrap();
+    } else {
+        let converter = Converter::new(cli.options.clone());
+        for arg in &cli.args {
+            match &cli.dec2hex {
+                Some(options) => println_bytes(&dec2hex_bytes(arg, options)),
+                None => println_bytes(&converter.hex2dec_bytes(arg)),
+            }
         }
     }
 }
@@ -0,0 +1,101 @@
+//! Rendering of numbers as human-readable sizes, e.g. `360.5 KiB`.
+
+/// Which units to render sizes with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Units {
+    /// Powers of 1024: KiB, MiB, GiB, ...
+    #[default]
+    Iec,
+    /// Powers of 1000: kB, MB, GB, ...
+    Si,
+}
+
+impl std::str::FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "iec" => Ok(Self::Iec),
+            "si" => Ok(Self::Si),
+            _ => Err(format!("invalid units: {s}")),
+        }
+    }
+}
+
+/// How to render numbers as human-readable sizes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sizes {
+    pub units: Units,
+    /// The number of decimals. Sizes below 1 KiB or 1 kB never have any.
+    pub precision: usize,
+    /// Keep the converted number and append the size, e.g.
+    /// `369152 (360.5 KiB)`, instead of replacing it, e.g. `360.5 KiB`.
+    pub append: bool,
+    /// Only render numbers right after one of these keywords as sizes, e.g.
+    /// `size` in `size=0x5a200` or `sh_size: 0x5a200`. All numbers if empty.
+    pub keywords: Vec<String>,
+}
+
+impl Sizes {
+    /// Formats `bytes` as a size, e.g. `360.5 KiB` or `42 B`.
+    pub(crate) fn format(&self, bytes: f64) -> String {
+        let (base, units) = match self.units {
+            Units::Iec => (
+                1024.0,
+                ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB", "ZiB", "YiB"],
+            ),
+            Units::Si => (
+                1000.0,
+                ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"],
+            ),
+        };
+        let mut size = bytes;
+        let mut unit = 0;
+        while size >= base && unit < units.len() - 1 {
+            size /= base;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{size} {}", units[unit])
+        } else {
+            format!(
+                "{size:.precision$} {}",
+                units[unit],
+                precision = self.precision
+            )
+        }
+    }
+
+    /// If the number at `start` in `line` is to be rendered as a size, see
+    /// [`Sizes::keywords`].
+    pub(crate) fn applies(&self, line: &[u8], start: usize) -> bool {
+        if self.keywords.is_empty() {
+            return true;
+        }
+        let before = &line[..start];
+        let before = &before[..before.len() - trailing(before, |b| b" \t=:".contains(&b))];
+        let word =
+            &before[before.len() - trailing(before, |b| b.is_ascii_alphanumeric() || b == b'_')..];
+        let word = String::from_utf8_lossy(word).to_ascii_lowercase();
+        self.keywords.iter().any(|keyword| {
+            let keyword = keyword.to_ascii_lowercase();
+            word.ends_with(&keyword) || word.split('_').any(|part| part.starts_with(&keyword))
+        })
+    }
+}
+
+/// The number of bytes at the end of `bytes` that match `predicate`.
+fn trailing(bytes: &[u8], predicate: impl Fn(u8) -> bool) -> usize {
+    bytes.iter().rev().take_while(|b| predicate(**b)).count()
+}
+
+impl Default for Sizes {
+    fn default() -> Self {
+        Self {
+            units: Units::default(),
+            precision: 1,
+            append: false,
+            keywords: vec![],
+        }
+    }
+}
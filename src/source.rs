@@ -0,0 +1,423 @@
+//! Conversion of source code, where only numeric literals are converted and
+//! identifiers, comments and strings are left alone.
+
+use crate::table::split_line_ending;
+use crate::{hex2dec_bytes_with, Converter, Options, Padding};
+use std::ops::Range;
+
+/// A programming language for [`hex2dec_source`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    /// C and C++, including `'` digit separators and raw strings.
+    C,
+    /// Rust, including raw strings, nested comments and lifetimes.
+    Rust,
+    /// Python, including triple-quoted strings.
+    Python,
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "c" | "c++" | "cpp" => Ok(Self::C),
+            "rust" => Ok(Self::Rust),
+            "python" => Ok(Self::Python),
+            _ => Err(format!("invalid language: {s}")),
+        }
+    }
+}
+
+/// Options for [`hex2dec_source`].
+#[derive(Clone, Debug)]
+pub struct SourceOptions {
+    pub language: Language,
+    /// Add the original literal as a comment after the converted one, e.g.
+    /// `16 /* 0x10 */`. Python has no inline comments, so the originals are
+    /// added as a comment at the end of the line instead, and literals on lines
+    /// that end in a string or a `\` continuation are left as is. Converted
+    /// literals aren't padded.
+    pub comments: bool,
+}
+
+/// Converts numeric literals with a radix prefix, e.g. `0x10`, in `source`.
+/// Numbers in identifiers, comments and strings are left alone, and so are
+/// decimal numbers. C-style octal numbers such as `0755` are only converted
+/// in C, and only with [`Options::octal`].
+pub fn hex2dec_source(source: &[u8], source_options: &SourceOptions, options: &Options) -> Vec<u8> {
+    let language = source_options.language;
+    let lexed = lex(source, language, options);
+    let converter = Converter::new(Options {
+        padding: if source_options.comments {
+            Padding::None
+        } else {
+            options.padding
+        },
+        ..options.clone()
+    });
+    let mut output = Vec::with_capacity(source.len());
+    let mut offset = 0;
+    // Literals and strings are sorted, so they are walked along with the lines.
+    let mut literals = lexed.literals.iter().peekable();
+    let mut strings = lexed.strings.iter().peekable();
+    for line in source.split_inclusive(|b| *b == b'\n') {
+        let mut line_literals = vec![];
+        while let Some(literal) = literals.next_if(|literal| literal.end <= offset + line.len()) {
+            line_literals.push(literal.start - offset..literal.end - offset);
+        }
+        let content = split_line_ending(line).0;
+        let end = offset + content.len();
+        while strings.next_if(|string| string.end <= end).is_some() {}
+        let in_string = strings.peek().is_some_and(|string| string.start < end);
+        let continued = content.ends_with(b"\\");
+        if source_options.comments && language == Language::Python && (in_string || continued) {
+            // There is no place for the comment with the originals.
+            line_literals.clear();
+        }
+        let mut originals = vec![];
+        let converted =
+            hex2dec_bytes_with(line, &converter, Some(&line_literals), |orig, output| {
+                if !source_options.comments {
+                    return;
+                }
+                match language {
+                    Language::C | Language::Rust => {
+                        output.extend_from_slice(format!(" /* {orig} */").as_bytes());
+                    }
+                    Language::Python => originals.push(orig.to_owned()),
+                }
+            });
+        if originals.is_empty() {
+            output.extend_from_slice(&converted);
+        } else {
+            let (content, ending) = split_line_ending(&converted);
+            output.extend_from_slice(content);
+            output.extend_from_slice(format!("  # {}", originals.join(", ")).as_bytes());
+            output.extend_from_slice(ending);
+        }
+        offset += line.len();
+    }
+    output
+}
+
+/// The ranges of interest in source code.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Lexed {
+    /// Numeric literals with a radix prefix.
+    literals: Vec<Range<usize>>,
+    /// String and character literals.
+    strings: Vec<Range<usize>>,
+}
+
+fn lex(source: &[u8], language: Language, options: &Options) -> Lexed {
+    let mut lexed = Lexed::default();
+    let mut pos = 0;
+    while pos < source.len() {
+        let rest = &source[pos..];
+        let len = match rest[0] {
+            b'/' if language != Language::Python && rest.starts_with(b"//") => line_len(rest),
+            b'/' if language != Language::Python && rest.starts_with(b"/*") => {
+                block_comment_len(rest, language == Language::Rust)
+            }
+            b'#' if language == Language::Python => line_len(rest),
+            b'"' => {
+                let len = string_len(rest, language);
+                lexed.strings.push(pos..pos + len);
+                len
+            }
+            b'\'' => match char_len(rest, language) {
+                Some(len) => {
+                    lexed.strings.push(pos..pos + len);
+                    len
+                }
+                None => 1,
+            },
+            b'0'..=b'9' => {
+                let len = number_len(rest, language);
+                if has_radix_prefix(rest, language, options) {
+                    lexed.literals.push(pos..pos + len);
+                }
+                len
+            }
+            b if is_ident(b) => {
+                let len = rest.iter().take_while(|b| is_ident(**b)).count();
+                match raw_string_len(&rest[..len], &rest[len..], language) {
+                    Some(raw_len) => {
+                        lexed.strings.push(pos..pos + len + raw_len);
+                        len + raw_len
+                    }
+                    None => len,
+                }
+            }
+            _ => 1,
+        };
+        pos += len;
+    }
+    lexed
+}
+
+/// Whether the number at the start of `rest` has a prefix for a radix that
+/// is converted with `options`: `0x` always, `0b` only with
+/// [`Options::binary`], and `0o` or, in C, `0` followed by an octal digit
+/// only with [`Options::octal`]. Decimal numbers such as `0e5` have none.
+fn has_radix_prefix(rest: &[u8], language: Language, options: &Options) -> bool {
+    match rest {
+        [b'0', b'x' | b'X', ..] => true,
+        [b'0', b'b' | b'B', ..] => options.binary,
+        [b'0', b'o' | b'O', ..] => options.octal,
+        [b'0', b'0'..=b'7', ..] => options.octal && language == Language::C,
+        _ => false,
+    }
+}
+
+/// Whether `b` can be part of an identifier. Bytes of non-ASCII characters
+/// are taken to be, which is close enough for numbers next to them.
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii()
+}
+
+/// The length of the rest of the line, without the line ending.
+fn line_len(rest: &[u8]) -> usize {
+    rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len())
+}
+
+fn block_comment_len(rest: &[u8], nested: bool) -> usize {
+    let mut depth = 0;
+    let mut pos = 0;
+    while pos < rest.len() {
+        if rest[pos..].starts_with(b"/*") && (nested || depth == 0) {
+            depth += 1;
+            pos += 2;
+        } else if rest[pos..].starts_with(b"*/") {
+            depth -= 1;
+            pos += 2;
+            if depth == 0 {
+                return pos;
+            }
+        } else {
+            pos += 1;
+        }
+    }
+    rest.len()
+}
+
+/// The length of the string at the start of `rest`, which starts with a quote.
+fn string_len(rest: &[u8], language: Language) -> usize {
+    let quote = rest[0];
+    if language == Language::Python && rest.starts_with(&[quote; 3]) {
+        return 3 + quoted_len(&rest[3..], &[quote; 3], true);
+    }
+    // Only Rust strings can span lines.
+    1 + quoted_len(&rest[1..], &[quote], language == Language::Rust)
+}
+
+/// The length up to and including the first `end` that isn't escaped, or up
+/// to the end of the line if `multiline` is false and there is none.
+fn quoted_len(rest: &[u8], end: &[u8], multiline: bool) -> usize {
+    let mut pos = 0;
+    while pos < rest.len() {
+        if rest[pos..].starts_with(end) {
+            return pos + end.len();
+        }
+        match rest[pos] {
+            b'\\' => pos += 2,
+            b'\n' if !multiline => return pos,
+            _ => pos += 1,
+        }
+    }
+    rest.len()
+}
+
+/// The length of the character literal at the start of `rest`, which starts
+/// with `'`, or `None` for a Rust lifetime or label.
+fn char_len(rest: &[u8], language: Language) -> Option<usize> {
+    match language {
+        Language::C | Language::Python => Some(string_len(rest, language)),
+        Language::Rust if rest.get(1) == Some(&b'\\') => Some(string_len(rest, language)),
+        Language::Rust => {
+            // The length of the UTF-8 encoded character after the quote.
+            let char_len = match rest.get(1) {
+                Some(0xf0..) => 4,
+                Some(0xe0..) => 3,
+                Some(0xc0..) => 2,
+                _ => 1,
+            };
+            (rest.get(1 + char_len) == Some(&b'\'')).then_some(char_len + 2)
+        }
+    }
+}
+
+/// The length of the raw string after the identifier `prefix`, e.g. `r` in
+/// `r#"..."#` in Rust or `R` in `R"x(...)x"` in C++, if there is one.
+fn raw_string_len(prefix: &[u8], rest: &[u8], language: Language) -> Option<usize> {
+    match language {
+        Language::Rust if matches!(prefix, b"r" | b"br" | b"cr") => {
+            let hashes = rest.iter().take_while(|b| **b == b'#').count();
+            if rest.get(hashes) != Some(&b'"') {
+                return None;
+            }
+            let mut end = vec![b'"'];
+            end.resize(1 + hashes, b'#');
+            let body = &rest[hashes + 1..];
+            let len = body
+                .windows(end.len())
+                .position(|window| window == end)
+                .map_or(body.len(), |pos| pos + end.len());
+            Some(hashes + 1 + len)
+        }
+        Language::C if prefix.ends_with(b"R") && rest.first() == Some(&b'"') => {
+            let delimiter_len = rest.iter().position(|b| *b == b'(')?;
+            let mut end = vec![b')'];
+            end.extend_from_slice(&rest[1..delimiter_len]);
+            end.push(b'"');
+            let body = &rest[delimiter_len + 1..];
+            let len = body
+                .windows(end.len())
+                .position(|window| window == end)
+                .map_or(body.len(), |pos| pos + end.len());
+            Some(delimiter_len + 1 + len)
+        }
+        _ => None,
+    }
+}
+
+/// The length of the number at the start of `rest`, including any suffix,
+/// separators, fraction and exponent.
+fn number_len(rest: &[u8], language: Language) -> usize {
+    let hex = rest.len() > 1 && rest[0] == b'0' && matches!(rest[1], b'x' | b'X');
+    let mut pos = 1;
+    while pos < rest.len() {
+        let next = rest.get(pos + 1).copied().unwrap_or(b' ');
+        let part_of_number = match rest[pos] {
+            b if b.is_ascii_alphanumeric() || b == b'_' => true,
+            b'\'' => language == Language::C && next.is_ascii_alphanumeric(),
+            b'.' => next.is_ascii_digit(),
+            b'+' | b'-' => match rest[pos - 1] {
+                b'p' | b'P' => hex,
+                b'e' | b'E' => !hex,
+                _ => false,
+            },
+            _ => false,
+        };
+        if !part_of_number {
+            break;
+        }
+        pos += 1;
+    }
+    pos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex2dec_source() {
+        let c = SourceOptions {
+            language: Language::C,
+            comments: false,
+        };
+        let rust = SourceOptions {
+            language: Language::Rust,
+            ..c.clone()
+        };
+        let python = SourceOptions {
+            language: Language::Python,
+            ..c.clone()
+        };
+        let tests = [
+            (&c, "x = 0x10; // 0x20", "x =   16; // 0x20"),
+            (
+                &c,
+                "f(\"0x10\", 'a', 0xdead'beefULL);",
+                "f(\"0x10\", 'a',  3735928559ULL);",
+            ),
+            (&c, "/* 0x10\n 0x20 */ 0x30", "/* 0x10\n 0x20 */   48"),
+            (&c, "int cafe_0x10 = 10;", "int cafe_0x10 = 10;"),
+            (
+                &c,
+                "R\"x(0x10)x\" 0x1.8p3 0x10",
+                "R\"x(0x10)x\"    12.0   16",
+            ),
+            (&rust, "let a: &'a u8 = 0xffu8;", "let a: &'a u8 =  255u8;"),
+            (&rust, "r#\"0x10\"# '\\'' 0x20", "r#\"0x10\"# '\\''   32"),
+            (&rust, "/* /* */ 0x10 */ 0x20", "/* /* */ 0x10 */   32"),
+            (&python, "x = 0xff  # 0x10", "x =  255  # 0x10"),
+            (&python, "'''0x10\n0x20''' 0x30", "'''0x10\n0x20'''   48"),
+            (
+                &rust,
+                "let a = 0b1010; let o = 0o17;",
+                "let a = 0b1010; let o = 0o17;",
+            ),
+            (
+                &c,
+                "double d = 0e5 + 0E10; int m = 0755;",
+                "double d = 0e5 + 0E10; int m = 0755;",
+            ),
+            (&python, "d = 0e5 + 0E10", "d = 0e5 + 0E10"),
+        ];
+        for test in tests {
+            let converted = hex2dec_source(test.1.as_bytes(), test.0, &Options::default());
+            assert_eq!(String::from_utf8(converted).unwrap(), test.2);
+        }
+
+        let binary_and_octal = Options {
+            binary: true,
+            octal: true,
+            ..Options::default()
+        };
+        let tests = [
+            (
+                &rust,
+                "let a = 0b1010; let o = 0o17; 0755",
+                "let a =     10; let o =   15; 0755",
+            ),
+            (
+                &c,
+                "double d = 0e5; int m = 0755;",
+                "double d = 0e5; int m =  493;",
+            ),
+            (&python, "d = 0e5 + 0o17", "d = 0e5 +   15"),
+        ];
+        for test in tests {
+            let converted = hex2dec_source(test.1.as_bytes(), test.0, &binary_and_octal);
+            assert_eq!(String::from_utf8(converted).unwrap(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_comments() {
+        let tests = [
+            (Language::C, "x = 0x10UL;\n", "x = 16UL /* 0x10UL */;\n"),
+            (
+                Language::Rust,
+                "[0x10, 0x20]",
+                "[16 /* 0x10 */, 32 /* 0x20 */]",
+            ),
+            (
+                Language::Python,
+                "f(0x10, 0x20)\r\n",
+                "f(16, 32)  # 0x10, 0x20\r\n",
+            ),
+            (
+                Language::Python,
+                "f(0x10, '''\n0x20''', 0x30)",
+                "f(0x10, '''\n0x20''', 48)  # 0x30",
+            ),
+            (
+                Language::Python,
+                "x = 0x10 + \\\n    0x20\n",
+                "x = 0x10 + \\\n    32  # 0x20\n",
+            ),
+        ];
+        for test in tests {
+            let options = SourceOptions {
+                language: test.0,
+                comments: true,
+            };
+            let converted = hex2dec_source(test.1.as_bytes(), &options, &Options::default());
+            assert_eq!(String::from_utf8(converted).unwrap(), test.2);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Structured tokens such as dates, version numbers and identifiers. They
+//! contain numbers that look like hex, but must be left alone as a whole.
+
+/// A shape of structured token to leave alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// UUIDs such as `550e8400-e29b-41d4-a716-446655440000`.
+    Uuid,
+    /// IPv6 addresses such as `2001:db8::ff00:42:8329` and `fe80::1/64`. A
+    /// `::` needs groups on both sides, two groups or a prefix length.
+    Ipv6,
+    /// MAC addresses such as `aa:bb:cc:dd:ee:ff` and `AA-BB-CC-DD-EE-FF`.
+    Mac,
+    /// IPv4 addresses such as `192.168.0.10`, with or without a port or
+    /// prefix length.
+    Ipv4,
+    /// Versions such as `v1.10` and `1.10.3-rc1`.
+    Version,
+    /// ISO 8601 dates such as `2024-10-17`.
+    Date,
+    /// Clock times such as `12:34` and `12:34:56.789`.
+    Time,
+    /// Hex digests without a `0x` prefix with as many digits as MD5, SHA-1,
+    /// and the SHA-2 family produce, such as full git commit hashes. They
+    /// must have both decimal digits and letters, so masks such as all `f`
+    /// are not hashes. Abbreviated hashes can't be told apart from addresses
+    /// and are not recognized.
+    Hash,
+}
+
+impl Shape {
+    /// All shapes, in the order they take precedence when they overlap.
+    pub const ALL: [Shape; 8] = [
+        Shape::Uuid,
+        Shape::Ipv6,
+        Shape::Mac,
+        Shape::Ipv4,
+        Shape::Version,
+        Shape::Date,
+        Shape::Time,
+        Shape::Hash,
+    ];
+
+    fn pattern(self) -> &'static str {
+        match self {
+            Shape::Uuid => {
+                r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}"
+            }
+            Shape::Ipv6 => {
+                // A lone `::` with at most one group, as in `ns::ff10`, is more
+                // likely a path in Rust or C++, unless it has a prefix length.
+                concat!(
+                    r"(?:(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}|[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*::[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*|[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})+::|::[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})+)(?:/[0-9]{1,3})?",
+                    r"|(?:[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*)?::(?:[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*)?/[0-9]{1,3}",
+                )
+            }
+            Shape::Mac => r"[0-9a-fA-F]{2}(?:[:-][0-9a-fA-F]{2}){5}",
+            Shape::Ipv4 => r"(?:[0-9]{1,3}\.){3}[0-9]{1,3}(?::[0-9]{1,5}|/[0-9]{1,2})?",
+            Shape::Version => {
+                r"(?:v[0-9]+(?:\.[0-9]+)+|[0-9]+\.[0-9]+\.[0-9]+)(?:-[0-9A-Za-z.]+)?(?:\+[0-9A-Za-z.]+)?"
+            }
+            Shape::Date => r"[0-9]{4}-[0-9]{2}-[0-9]{2}",
+            Shape::Time => r"[0-9]{1,2}:[0-9]{2}(?::[0-9]{2}(?:[.,][0-9]+)?)?",
+            Shape::Hash => {
+                r"[0-9a-fA-F]{128}|[0-9a-fA-F]{96}|[0-9a-fA-F]{64}|[0-9a-fA-F]{56}|[0-9a-fA-F]{40}|[0-9a-fA-F]{32}"
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uuid" => Ok(Self::Uuid),
+            "ipv6" => Ok(Self::Ipv6),
+            "mac" => Ok(Self::Mac),
+            "ipv4" => Ok(Self::Ipv4),
+            "version" => Ok(Self::Version),
+            "date" => Ok(Self::Date),
+            "time" => Ok(Self::Time),
+            "hash" => Ok(Self::Hash),
+            _ => Err(format!("invalid shape: {s}")),
+        }
+    }
+}
+
+/// Whether a match of [`pattern`] is to be left alone. The regex crate has no
+/// lookahead, so this checks what the pattern can't: that a [`Shape::Hash`]
+/// has both decimal digits and letters.
+pub(crate) fn is_skipped(matched: &[u8]) -> bool {
+    !matched.iter().all(u8::is_ascii_hexdigit)
+        || (matched.iter().any(u8::is_ascii_digit) && matched.iter().any(u8::is_ascii_alphabetic))
+}
+
+/// Returns a regex pattern that matches any of `shapes` as a whole, or `None`
+/// if `shapes` is empty.
+pub fn pattern(shapes: &[Shape]) -> Option<String> {
+    let alternatives: Vec<&str> = Shape::ALL
+        .into_iter()
+        .filter(|shape| shapes.contains(shape))
+        .map(Shape::pattern)
+        .collect();
+    (!alternatives.is_empty()).then(|| format!(r"\b(?:{})\b", alternatives.join("|")))
+}
@@ -0,0 +1,283 @@
+//! Conversion of tabular output such as `readelf -S`, `/proc/pid/maps` and
+//! `nm`, with columns realigned after conversion so the table still lines up.
+
+use crate::{converted_ranges, Converter, Detection, Options, Padding};
+use std::io::{BufRead, Write};
+use std::ops::Range;
+
+/// Converts a block of `lines`, without line endings, as a table. Columns are
+/// the runs of characters that are not whitespace in at least one line. A
+/// column is taken to be all hex, see [`Detection::Strict`], if any of its
+/// numbers is hex, either evidently or in the context of its line like with
+/// [`crate::hex2dec_bytes`], e.g. `0018` after `001c`. After conversion, converted numbers are
+/// right-aligned in the width of the widest cell of their column. Other cells
+/// keep their original bytes, including leading spaces, so that they stay
+/// aligned with each other and only shift as a whole. Lines are unchanged if
+/// no number in the block is converted. Cells are converted on their own, so
+/// [`crate::Sizes::keywords`] only apply to numbers in the same cell.
+pub fn hex2dec_table(lines: &[&[u8]], options: &Options) -> Vec<Vec<u8>> {
+    convert_table(lines, &Converter::new(options.clone()))
+}
+
+/// Like [`hex2dec_table`] with the options of `converter`.
+fn convert_table(lines: &[&[u8]], converter: &Converter) -> Vec<Vec<u8>> {
+    let options = converter.options();
+    let columns = columns(lines);
+    let cells: Vec<Vec<&[u8]>> = lines
+        .iter()
+        .map(|line| {
+            columns
+                .iter()
+                .map(|column| trim(&line[column_range(line, column)]))
+                .collect()
+        })
+        .collect();
+
+    let cell_converter = converter.with_options(Options {
+        padding: Padding::None,
+        ..options.clone()
+    });
+    let loose_converter = converter.with_options(Options {
+        detection: Detection::Loose,
+        ..cell_converter.options().clone()
+    });
+    // The numbers that would be converted in line mode, by line.
+    let hex_ranges: Vec<Vec<Range<usize>>> = lines
+        .iter()
+        .map(|line| converted_ranges(line, converter))
+        .collect();
+    // The cells after conversion, and whether they changed.
+    let mut converted: Vec<Vec<(Vec<u8>, bool)>> = vec![vec![]; lines.len()];
+    let mut widths = vec![0; columns.len()];
+    for column in 0..columns.len() {
+        let convert = |converter: &Converter| -> Vec<Vec<u8>> {
+            cells
+                .iter()
+                .map(|cells| converter.hex2dec_bytes(cells[column]).into_owned())
+                .collect()
+        };
+        let mut column_cells = convert(&cell_converter);
+        let changed =
+            |column_cells: &[Vec<u8>], row: usize| column_cells[row] != cells[row][column];
+        let bounds = &columns[column];
+        let hex_in_line = |row: usize| {
+            hex_ranges[row]
+                .iter()
+                .any(|range| range.start < bounds.end && bounds.start < range.end)
+        };
+        if options.detection == Detection::Strict
+            && (0..lines.len()).any(|row| changed(&column_cells, row) || hex_in_line(row))
+        {
+            column_cells = convert(&loose_converter);
+        }
+        for (row, cell) in column_cells.iter().enumerate() {
+            let line = lines[row];
+            let cell = if changed(&column_cells, row) {
+                (cell.clone(), true)
+            } else {
+                // Keep the original bytes, so unchanged cells stay aligned
+                // with each other.
+                (
+                    trim_end(&line[column_range(line, &columns[column])]).to_vec(),
+                    false,
+                )
+            };
+            widths[column] = widths[column].max(width(&cell.0));
+            converted[row].push(cell);
+        }
+    }
+    if !converted.iter().flatten().any(|(_, changed)| *changed) {
+        return lines.iter().map(|line| line.to_vec()).collect();
+    }
+
+    lines
+        .iter()
+        .zip(converted)
+        .map(|(line, cells)| {
+            let mut realigned =
+                line[..columns.first().map_or(0, |c| c.start.min(line.len()))].to_vec();
+            for (column, (cell, changed)) in cells.iter().enumerate() {
+                let padding = vec![b' '; widths[column] - width(cell)];
+                if *changed {
+                    realigned.extend_from_slice(&padding);
+                    realigned.extend_from_slice(cell);
+                } else {
+                    realigned.extend_from_slice(cell);
+                    realigned.extend_from_slice(&padding);
+                }
+                if let Some(next) = columns.get(column + 1) {
+                    let gap = columns[column].end..next.start;
+                    match line.get(gap.clone()) {
+                        Some(gap) => realigned.extend_from_slice(gap),
+                        None => realigned.resize(realigned.len() + gap.len(), b' '),
+                    }
+                }
+            }
+            let trailing = line.len() - trim_end(line).len();
+            realigned.truncate(trim_end(&realigned).len());
+            realigned.extend_from_slice(&line[line.len() - trailing..]);
+            realigned
+        })
+        .collect()
+}
+
+/// Like [`crate::transform_stream`] with [`hex2dec_table`]. Blocks of lines
+/// are separated by blank lines, which are passed through unchanged.
+pub fn hex2dec_table_stream(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    options: &Options,
+) -> std::io::Result<()> {
+    let converter = Converter::new(options.clone());
+    let mut block: Vec<Vec<u8>> = vec![];
+    let mut line = vec![];
+    loop {
+        line.clear();
+        let eof = input.read_until(b'\n', &mut line)? == 0;
+        if eof || trim(&line).is_empty() {
+            let (contents, endings): (Vec<&[u8]>, Vec<&[u8]>) =
+                block.iter().map(|line| split_line_ending(line)).unzip();
+            for (converted, ending) in convert_table(&contents, &converter).iter().zip(endings) {
+                output.write_all(converted)?;
+                output.write_all(ending)?;
+            }
+            block.clear();
+            output.write_all(&line)?;
+        } else {
+            block.push(line.clone());
+        }
+        if eof {
+            break;
+        }
+    }
+    output.flush()
+}
+
+/// Returns the ranges of the columns in `lines`, i.e. the runs of positions
+/// that are not whitespace in at least one line.
+fn columns(lines: &[&[u8]]) -> Vec<Range<usize>> {
+    let mut gutter = vec![true; lines.iter().map(|line| line.len()).max().unwrap_or(0)];
+    for line in lines {
+        for (gutter, byte) in gutter.iter_mut().zip(*line) {
+            *gutter &= byte.is_ascii_whitespace();
+        }
+    }
+    let mut columns: Vec<Range<usize>> = vec![];
+    for (pos, gutter) in gutter.into_iter().enumerate() {
+        match columns.last_mut() {
+            Some(column) if !gutter && column.end == pos => column.end += 1,
+            _ if !gutter => columns.push(pos..pos + 1),
+            _ => {}
+        }
+    }
+    columns
+}
+
+/// The part of `column` that `line` has bytes for.
+fn column_range(line: &[u8], column: &Range<usize>) -> Range<usize> {
+    column.start.min(line.len())..column.end.min(line.len())
+}
+
+pub(crate) fn split_line_ending(line: &[u8]) -> (&[u8], &[u8]) {
+    let content = line.strip_suffix(b"\n").unwrap_or(line);
+    let content = content.strip_suffix(b"\r").unwrap_or(content);
+    line.split_at(content.len())
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let trimmed = trim_end(bytes);
+    let leading = trimmed
+        .iter()
+        .take_while(|b| b.is_ascii_whitespace())
+        .count();
+    &trimmed[leading..]
+}
+
+fn trim_end(bytes: &[u8]) -> &[u8] {
+    let trailing = bytes
+        .iter()
+        .rev()
+        .take_while(|b| b.is_ascii_whitespace())
+        .count();
+    &bytes[..bytes.len() - trailing]
+}
+
+/// The width of `cell` in characters.
+fn width(cell: &[u8]) -> usize {
+    String::from_utf8_lossy(cell).chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex2dec_table() {
+        let tests: [(&[&str], &[&str]); 6] = [
+            (&[], &[]),
+            (&["took 10 ms", "took 20 ms"], &["took 10 ms", "took 20 ms"]),
+            (
+                &[
+                    "7f1c2a3b4000-7f1c2a3b5000 r--p 00000000 08:01 123 /usr/lib/libc.so",
+                    "7f1c2a3b5000-7f1c2a3b6000 r-xp 0001c000 08:01 123 /usr/lib/libc.so",
+                ],
+                &[
+                    "139758944337920-139758944342016 r--p      0 08:01 123 /usr/lib/libc.so",
+                    "139758944342016-139758944346112 r-xp 114688 08:01 123 /usr/lib/libc.so",
+                ],
+            ),
+            (
+                &[
+                    "0000000000001040 T main",
+                    "                 U printf",
+                    "0000000000004010 B counter  ",
+                    "00000000000011a0 t helper",
+                ],
+                &[
+                    " 4160 T main",
+                    "      U printf",
+                    "16400 B counter  ",
+                    " 4512 t helper",
+                ],
+            ),
+            (
+                &[
+                    "  [Nr] Name              Type             Address           Offset",
+                    "       Size              EntSize          Flags  Link  Info  Align",
+                    "  [ 0]                   NULL             0000000000000000  00000000",
+                    "       0000000000000000  0000000000000000           0     0     0",
+                    "  [ 1] .interp           PROGBITS         0000000000000318  00000318",
+                    "       000000000000001c  0000000000000000   A       0     0     1",
+                    "  [ 2] .dynsym           DYNSYM           00000000000003d8  000003d8",
+                    "       0000000000000a68  0000000000000018   A       7     1     8",
+                ],
+                &[
+                    "  [Nr] Name     Type     Address           Offset",
+                    "       Size     EntSize  Flags  Link  Info  Align",
+                    "  [ 0]          NULL                     0      0",
+                    "             0         0           0     0     0",
+                    "  [ 1] .interp  PROGBITS               792    792",
+                    "            28         0   A       0     0     1",
+                    "  [ 2] .dynsym  DYNSYM                 984    984",
+                    "          2664        24   A       7     1     8",
+                ],
+            ),
+            (
+                &[
+                    ".interp  0000000000000318  0000000000000018",
+                    ".dynsym  00000000000003d8  0000000000000018",
+                ],
+                &[".interp  792  24", ".dynsym  984  24"],
+            ),
+        ];
+        for test in tests {
+            let lines: Vec<&[u8]> = test.0.iter().map(|line| line.as_bytes()).collect();
+            let converted = hex2dec_table(&lines, &Options::default());
+            let converted: Vec<String> = converted
+                .into_iter()
+                .map(|line| String::from_utf8(line).unwrap())
+                .collect();
+            assert_eq!(converted, test.1);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Templates for what to replace numbers with, e.g. `{dec}/{hex}`.
+
+use crate::bigint::BigUint;
+use crate::size::Sizes;
+
+/// A template with placeholders for a converted number:
+///
+/// * `{value}`: the converted number, in the radix to convert to
+/// * `{dec}`: the number in decimal
+/// * `{orig}`: the number as it was found, e.g. `0x5a200`
+/// * `{hex}`: the bare hex digits as found, e.g. `00FF` for `0x00FF`, or
+///   the number in hex for binary and octal numbers
+/// * `{prefix}`: the prefix of the number as it was found, e.g. `0x`
+/// * `{bits}`: the number of significant bits of the number
+/// * `{size}`: the number as a human-readable size, e.g. `360.5 KiB`
+///
+/// For floats, `{value}` is the float, and `{dec}`, `{hex}` and `{bits}` are
+/// of the bit pattern, or of the digits without the point for hex floats.
+///
+/// Literal braces are written as `{{` and `}}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placeholder {
+    Value,
+    Dec,
+    Orig,
+    Hex,
+    Prefix,
+    Bits,
+    Size,
+}
+
+/// A number to render with a [`Template`].
+pub(crate) struct Number<'a> {
+    /// The number as it was found, e.g. `0x5a200`.
+    pub orig: &'a str,
+    /// The prefix of the number as it was found, e.g. `0x`.
+    pub prefix: &'a str,
+    /// The bare hex digits as found, e.g. `5a200`.
+    pub hex: &'a str,
+    pub value: &'a BigUint,
+    /// The value in the radix to convert to.
+    pub converted: &'a str,
+    /// How to render `{size}`.
+    pub sizes: &'a Sizes,
+}
+
+impl Template {
+    pub(crate) fn render(&self, number: &Number) -> String {
+        let mut rendered = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(literal) => rendered += literal,
+                Piece::Placeholder(placeholder) => match placeholder {
+                    Placeholder::Value => rendered += number.converted,
+                    Placeholder::Dec => rendered += &number.value.to_str_radix(10),
+                    Placeholder::Orig => rendered += number.orig,
+                    Placeholder::Hex => rendered += number.hex,
+                    Placeholder::Prefix => rendered += number.prefix,
+                    Placeholder::Bits => rendered += &number.value.bits().to_string(),
+                    Placeholder::Size => rendered += &number.sizes.format(number.value.to_f64()),
+                },
+            }
+        }
+        rendered
+    }
+}
+
+impl std::str::FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pieces = vec![];
+        let mut literal = String::new();
+        let mut rest = s;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("{{").or(rest.strip_prefix("}}")) {
+                literal.push(c);
+                rest = after;
+            } else if c == '{' {
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| format!("unclosed placeholder in template: {s}"))?;
+                let placeholder = match &rest[..=end] {
+                    "{value}" => Placeholder::Value,
+                    "{dec}" => Placeholder::Dec,
+                    "{orig}" => Placeholder::Orig,
+                    "{hex}" => Placeholder::Hex,
+                    "{prefix}" => Placeholder::Prefix,
+                    "{bits}" => Placeholder::Bits,
+                    "{size}" => Placeholder::Size,
+                    unknown => return Err(format!("unknown placeholder in template: {unknown}")),
+                };
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Placeholder(placeholder));
+                rest = &rest[end + 1..];
+            } else if c == '}' {
+                return Err(format!("unmatched }} in template: {s}"));
+            } else {
+                literal.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Ok(Self { pieces })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let value = BigUint::from_str_radix("5a200", 16).unwrap();
+        let number = Number {
+            orig: "0x5a200",
+            prefix: "0x",
+            hex: "5a200",
+            value: &value,
+            converted: "1320400",
+            sizes: &Sizes::default(),
+        };
+        let tests = [
+            ("", ""),
+            ("{dec}/{hex}", "369152/5a200"),
+            (
+                "{orig} = {value} ({bits} bits)",
+                "0x5a200 = 1320400 (19 bits)",
+            ),
+            ("{prefix}{hex}: {size}", "0x5a200: 360.5 KiB"),
+            ("{{dec}} {{{dec}}}", "{dec} {369152}"),
+        ];
+        for test in tests {
+            let template: Template = test.0.parse().unwrap();
+            assert_eq!(template.render(&number), test.1);
+        }
+        for invalid in ["{", "{dec", "}", "{foo}"] {
+            assert!(invalid.parse::<Template>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! English words made up of only the letters a-f, which are valid hex numbers
+//! but almost never meant as such.
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+
+static WORDS: Lazy<HashSet<&str>> = Lazy::new(|| include_str!("words.txt").lines().collect());
+
+/// If `word` is in the embedded word list, ignoring case.
+pub fn is_english_word(word: &str) -> bool {
+    WORDS.contains(word.to_ascii_lowercase().as_str())
+}